@@ -0,0 +1,122 @@
+use crate::bits::{decode_bcd, encode_bcd, get_bits};
+use crate::datetime::DayOfWeek;
+use crate::typedefs::Hours;
+
+// AEN_x bit; when set the field is ignored for alarm matching
+const ALARM_DISABLED: u8 = 0b1000_0000;
+
+// each field is optional and only enabled fields take part in the match
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Alarm {
+    minute: Option<u8>,
+    hour: Option<u8>,
+    day: Option<u8>,
+    weekday: Option<DayOfWeek>,
+}
+
+impl Alarm {
+    pub fn new(
+        minute: Option<u8>,
+        hour: Option<u8>,
+        day: Option<u8>,
+        weekday: Option<DayOfWeek>,
+    ) -> Option<Self> {
+        if minute.is_some_and(|m| m > 59) { return None; }
+        if hour.is_some_and(|h| h > 23) { return None; }
+        if day.is_some_and(|d| d == 0 || d > 31) { return None; }
+
+        Some(Self { minute, hour, day, weekday })
+    }
+
+    pub fn minute(&self) -> Option<u8> {
+        self.minute
+    }
+
+    pub fn hour(&self) -> Option<u8> {
+        self.hour
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+
+    pub fn weekday(&self) -> Option<DayOfWeek> {
+        self.weekday
+    }
+
+    // register values for Minute_alarm, Hour_alarm, Day_alarm and Weekday_alarm, in that order
+    // hour is held in 24-hour format and Hour_alarm follows the 12_24 bit of CONTROL_1
+    pub(crate) fn encode(&self, mode_12hr: bool) -> [u8; 4] {
+        [
+            self.minute.map_or(ALARM_DISABLED, encode_bcd),
+            self.hour.map_or(ALARM_DISABLED, |h| Hours::from_24_hour(h, mode_12hr).encode()),
+            self.day.map_or(ALARM_DISABLED, encode_bcd),
+            self.weekday.map_or(ALARM_DISABLED, |w| w as u8),
+        ]
+    }
+
+    pub(crate) fn decode(regs: [u8; 4], mode_12hr: bool) -> Option<Self> {
+        let [minute, hour, day, weekday] = regs;
+        let weekday = if is_enabled(weekday) {
+            Some(DayOfWeek::try_from(get_bits(weekday, 3, 0)).ok()?)
+        } else {
+            None
+        };
+
+        Some(Self {
+            minute: is_enabled(minute).then(|| decode_bcd(get_bits(minute, 7, 0))),
+            hour: is_enabled(hour).then(|| Hours::decode(hour, mode_12hr).to_24_hour()),
+            day: is_enabled(day).then(|| decode_bcd(get_bits(day, 6, 0))),
+            weekday,
+        })
+    }
+}
+
+fn is_enabled(reg: u8) -> bool {
+    reg & ALARM_DISABLED == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_invalid_minute_none() {
+        assert!(Alarm::new(Some(60), None, None, None).is_none());
+    }
+
+    #[test]
+    fn new_invalid_hour_none() {
+        assert!(Alarm::new(None, Some(24), None, None).is_none());
+    }
+
+    #[test]
+    fn new_invalid_day_none() {
+        assert!(Alarm::new(None, None, Some(0), None).is_none());
+        assert!(Alarm::new(None, None, Some(32), None).is_none());
+    }
+
+    #[test]
+    fn encode_disabled_fields() {
+        let alarm = Alarm::new(Some(30), None, None, Some(DayOfWeek::Friday)).unwrap();
+        assert_eq!(alarm.encode(false), [0b0011_0000, 0b1000_0000, 0b1000_0000, 0b101]);
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        let alarm = Alarm::new(Some(45), Some(23), Some(31), None).unwrap();
+        assert_eq!(Alarm::decode(alarm.encode(false), false), Some(alarm));
+    }
+
+    #[test]
+    fn encode_12_hour_mode() {
+        let alarm = Alarm::new(None, Some(18), None, None).unwrap();
+        assert_eq!(alarm.encode(true)[1], 0b10_0110);
+        assert_eq!(Alarm::decode(alarm.encode(true), true), Some(alarm));
+    }
+
+    #[test]
+    fn decode_invalid_weekday_none() {
+        assert!(Alarm::decode([0x80, 0x80, 0x80, 0b111], false).is_none());
+    }
+}
@@ -5,7 +5,7 @@ pub(crate) fn decode_bcd(a: u8) -> u8 {
 
 pub(crate) fn encode_bcd(a: u8) -> u8 {
     if a >= 100 { panic!("Cannot BCD encode value {} as u8", a); }
-    a % 10 | (a / 10 << 4)
+    (a % 10) | ((a / 10) << 4)
 }
 
 pub(crate) fn get_bits(byte: u8, bits: u8, lsb_offset: u8) -> u8 {
@@ -0,0 +1,62 @@
+use crate::registers::{PCF8523_CONTROL_1, PCF8523_TMR_A_FREQ_CTRL, PCF8523_TMR_B_FREQ_CTRL, PCF8523_TMR_CLKOUT_CTRL};
+
+// registers whose bits only change when written; CONTROL_2 and CONTROL_3 hold flags set by the chip and the
+// timer value registers count down so they are always read from the bus
+const CACHED_REGISTERS: [u8; 4] = [PCF8523_CONTROL_1, PCF8523_TMR_CLKOUT_CTRL, PCF8523_TMR_A_FREQ_CTRL, PCF8523_TMR_B_FREQ_CTRL];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct RegisterCache {
+    values: [Option<u8>; 4],
+}
+
+impl RegisterCache {
+    pub(crate) fn get(&self, reg: u8) -> Option<u8> {
+        self.values[index(reg)?]
+    }
+
+    // stores the values of consecutive registers starting at start, skipping the ones that are not cached
+    pub(crate) fn update(&mut self, start: u8, values: &[u8]) {
+        for (reg, value) in (start..).zip(values) {
+            if let Some(i) = index(reg) { self.values[i] = Some(*value); }
+        }
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        self.values = [None; 4];
+    }
+}
+
+fn index(reg: u8) -> Option<usize> {
+    CACHED_REGISTERS.iter().position(|r| *r == reg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registers::{PCF8523_CONTROL_2, PCF8523_TMR_A_REG};
+
+    #[test]
+    fn update_skips_uncached_registers() {
+        let mut cache = RegisterCache::default();
+        cache.update(PCF8523_TMR_A_FREQ_CTRL, &[0b10, 0xff, 0b11]);
+        assert_eq!(cache.get(PCF8523_TMR_A_FREQ_CTRL), Some(0b10));
+        assert_eq!(cache.get(PCF8523_TMR_A_REG), None);
+        assert_eq!(cache.get(PCF8523_TMR_B_FREQ_CTRL), Some(0b11));
+    }
+
+    #[test]
+    fn control_2_not_cached() {
+        let mut cache = RegisterCache::default();
+        cache.update(PCF8523_CONTROL_1, &[0b1, 0b1000]);
+        assert_eq!(cache.get(PCF8523_CONTROL_1), Some(0b1));
+        assert_eq!(cache.get(PCF8523_CONTROL_2), None);
+    }
+
+    #[test]
+    fn invalidate_clears_all() {
+        let mut cache = RegisterCache::default();
+        cache.update(PCF8523_CONTROL_1, &[0b1]);
+        cache.invalidate();
+        assert_eq!(cache, RegisterCache::default());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::datetime::DateTime;
+use crate::offset::{round, Offset, OffsetMode};
+
+// drift of the RTC against an external reference (GPS PPS, NTP disciplined host clock, etc.)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DriftMeasurement {
+    drift_ppm: f32,
+}
+
+impl DriftMeasurement {
+    // reference timestamps are in milliseconds on any monotonic timescale and must be taken at the same
+    // instants as the two RTC readings, returns None if the reference interval is empty or negative
+    pub fn new(reference_start_ms: u64, rtc_start: &DateTime, reference_end_ms: u64, rtc_end: &DateTime) -> Option<Self> {
+        let reference_elapsed_ms = reference_end_ms.checked_sub(reference_start_ms).filter(|ms| *ms > 0)? as i64;
+        let rtc_elapsed_ms = (rtc_end.seconds_since_2000() as i64 - rtc_start.seconds_since_2000() as i64) * 1000;
+        let drift_ppm = (rtc_elapsed_ms - reference_elapsed_ms) as f32 / reference_elapsed_ms as f32 * 1_000_000.0;
+        Some(Self { drift_ppm })
+    }
+
+    // positive when the RTC runs fast
+    pub fn drift_ppm(&self) -> f32 {
+        self.drift_ppm
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    drift_ppm: f32,
+    offset: Offset,
+    residual_ppm: f32,
+    exceeds_range: bool,
+}
+
+impl Calibration {
+    // current is the offset that was active while the drift was measured
+    pub fn new(current: Offset, mode: OffsetMode, measurement: DriftMeasurement) -> Self {
+        let drift_ppm = measurement.drift_ppm();
+        let steps = round((current.ppm() - drift_ppm) / mode.step_ppm());
+        let offset = Offset::clamped(mode, steps);
+        Self {
+            drift_ppm,
+            offset,
+            residual_ppm: drift_ppm + offset.ppm() - current.ppm(),
+            exceeds_range: steps != offset.value() as i32,
+        }
+    }
+
+    // measured error, positive when the RTC runs fast
+    pub fn drift_ppm(&self) -> f32 {
+        self.drift_ppm
+    }
+
+    // setting that corrects the measured drift, clamped to the register range
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    // drift expected to remain once offset is applied
+    pub fn residual_ppm(&self) -> f32 {
+        self.residual_ppm
+    }
+
+    // true if the required correction is outside the register range and offset was clamped
+    pub fn exceeds_range(&self) -> bool {
+        self.exceeds_range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::{DayOfWeek, Month};
+
+    fn dt(seconds: u8, minutes: u8, hours: u8, day: u8) -> DateTime {
+        DateTime::new(seconds, minutes, hours, day, DayOfWeek::Sunday, Month::January, 25).unwrap()
+    }
+
+    #[test]
+    fn drift_measurement_empty_interval_none() {
+        assert!(DriftMeasurement::new(1000, &dt(0, 0, 0, 1), 1000, &dt(0, 0, 0, 2)).is_none());
+        assert!(DriftMeasurement::new(2000, &dt(0, 0, 0, 1), 1000, &dt(0, 0, 0, 2)).is_none());
+    }
+
+    #[test]
+    fn drift_measurement_fast_clock() {
+        // RTC gained 1 s over 100 000 s
+        let measurement = DriftMeasurement::new(0, &dt(0, 0, 0, 1), 100_000_000, &dt(41, 46, 3, 2)).unwrap();
+        assert!((measurement.drift_ppm() - 10.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn calibration_corrects_current_offset() {
+        let current = Offset::new(OffsetMode::EveryTwoHours, -2).unwrap();
+        let measurement = DriftMeasurement { drift_ppm: 4.34 };
+        let calibration = Calibration::new(current, OffsetMode::EveryTwoHours, measurement);
+        assert_eq!(calibration.offset().value(), -3);
+        assert!(!calibration.exceeds_range());
+        assert!(calibration.residual_ppm().abs() < 0.001);
+    }
+
+    #[test]
+    fn calibration_exceeds_range_clamped() {
+        let current = Offset::new(OffsetMode::EveryMinute, 0).unwrap();
+        let measurement = DriftMeasurement { drift_ppm: -300.0 };
+        let calibration = Calibration::new(current, OffsetMode::EveryMinute, measurement);
+        assert_eq!(calibration.offset().value(), Offset::MAX);
+        assert!(calibration.exceeds_range());
+        assert!(calibration.residual_ppm() < -40.0);
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+use crate::datetime::{full_year, year_in_century, DateTime, DateTimeError, DEFAULT_CENTURY};
+use crate::driver::Pcf8523;
+#[cfg(feature = "async")]
+use crate::driver::Pcf8523Async;
+use crate::typedefs::Pcf8523Error;
+
+impl DateTime {
+    pub fn from_naive_datetime(datetime: &NaiveDateTime, century: u16) -> Result<Self, DateTimeError> {
+        let mut dt = DateTime::default();
+        dt.set_naive_date(&datetime.date(), century)?;
+        dt.set_naive_time(&datetime.time());
+        Ok(dt)
+    }
+
+    pub fn to_naive_datetime(&self, century: u16) -> Result<NaiveDateTime, DateTimeError> {
+        Ok(self.to_naive_date(century)?.and_time(self.to_naive_time()?))
+    }
+
+    pub(crate) fn to_naive_date(self, century: u16) -> Result<NaiveDate, DateTimeError> {
+        let year = full_year(self.year, century)?;
+        if !(1..=12).contains(&self.month) { return Err(DateTimeError::InvalidMonth); }
+        NaiveDate::from_ymd_opt(year, self.month as u32, self.day as u32)
+            .ok_or(DateTimeError::InvalidDay)
+    }
+
+    pub(crate) fn to_naive_time(self) -> Result<NaiveTime, DateTimeError> {
+        if self.hours > 23 { return Err(DateTimeError::InvalidHours); }
+        if self.minutes > 59 { return Err(DateTimeError::InvalidMinutes); }
+        NaiveTime::from_hms_opt(self.hours as u32, self.minutes as u32, self.seconds as u32)
+            .ok_or(DateTimeError::InvalidSeconds)
+    }
+
+    pub(crate) fn set_naive_date(&mut self, date: &NaiveDate, century: u16) -> Result<(), DateTimeError> {
+        let year = year_in_century(date.year(), century)?;
+        self.day = date.day() as u8;
+        self.day_of_week = date.weekday().num_days_from_sunday() as u8;
+        self.month = date.month() as u8;
+        self.year = year;
+        Ok(())
+    }
+
+    pub(crate) fn set_naive_time(&mut self, time: &NaiveTime) {
+        self.seconds = time.second() as u8;
+        self.minutes = time.minute() as u8;
+        self.hours = time.hour() as u8;
+    }
+}
+
+impl TryFrom<NaiveDateTime> for DateTime {
+    type Error = DateTimeError;
+    fn try_from(value: NaiveDateTime) -> Result<Self, Self::Error> {
+        DateTime::from_naive_datetime(&value, DEFAULT_CENTURY)
+    }
+}
+
+impl TryFrom<DateTime> for NaiveDateTime {
+    type Error = DateTimeError;
+    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
+        value.to_naive_datetime(DEFAULT_CENTURY)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(Pcf8523(sync = "Pcf8523", async = "Pcf8523Async"), I2c(sync = "I2c", async = "AsyncI2c")),
+    sync(keep_self),
+    async(feature = "async"),
+)]
+impl<I2C: I2c> Pcf8523<I2C> {
+    pub async fn get_naive_datetime(&mut self) -> Result<NaiveDateTime, Pcf8523Error<I2C::Error>> {
+        self.get_datetime().await?.to_naive_datetime(DEFAULT_CENTURY).map_err(Pcf8523Error::DateTime)
+    }
+
+    pub async fn set_naive_datetime(&mut self, datetime: &NaiveDateTime) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let dt = DateTime::from_naive_datetime(datetime, DEFAULT_CENTURY).map_err(Pcf8523Error::DateTime)?;
+        self.set_datetime(dt).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::{DayOfWeek, Month};
+
+    #[test]
+    fn to_naive_datetime_invalid_day_err() {
+        let dt = DateTime { day: 30, month: 2, year: 25, ..DateTime::default() };
+        assert_eq!(NaiveDateTime::try_from(dt).unwrap_err(), DateTimeError::InvalidDay);
+    }
+
+    #[test]
+    fn to_naive_datetime_round_trip() {
+        let dt = DateTime::new(1, 2, 3, 29, DayOfWeek::Thursday, Month::February, 24).unwrap();
+        let ndt = dt.to_naive_datetime(2000).unwrap();
+        assert_eq!(ndt, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap().and_hms_opt(3, 2, 1).unwrap());
+        assert_eq!(DateTime::try_from(ndt).unwrap(), dt);
+    }
+}
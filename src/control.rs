@@ -0,0 +1,269 @@
+use crate::bits::{get_bits, set_bits};
+use crate::typedefs::PowerManagement;
+
+// Control_1, Control_2 and Control_3 register values, setters only change the value held here
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Control1(u8);
+impl Control1 {
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    // 12.5 pF oscillator capacitor when set, 7 pF otherwise
+    pub fn cap_sel(&self) -> bool {
+        bit(self.0, 7)
+    }
+
+    pub fn set_cap_sel(&mut self, cap_sel: bool) {
+        set_bit(&mut self.0, 7, cap_sel);
+    }
+
+    // unused, must be 0
+    pub fn t(&self) -> bool {
+        bit(self.0, 6)
+    }
+
+    pub fn set_t(&mut self, t: bool) {
+        set_bit(&mut self.0, 6, t);
+    }
+
+    pub fn stop(&self) -> bool {
+        bit(self.0, 5)
+    }
+
+    pub fn set_stop(&mut self, stop: bool) {
+        set_bit(&mut self.0, 5, stop);
+    }
+
+    pub fn sr(&self) -> bool {
+        bit(self.0, 4)
+    }
+
+    pub fn set_sr(&mut self, sr: bool) {
+        set_bit(&mut self.0, 4, sr);
+    }
+
+    // 12_24
+    pub fn hour_mode_12hr(&self) -> bool {
+        bit(self.0, 3)
+    }
+
+    pub fn set_hour_mode_12hr(&mut self, mode_12hr: bool) {
+        set_bit(&mut self.0, 3, mode_12hr);
+    }
+
+    pub fn sie(&self) -> bool {
+        bit(self.0, 2)
+    }
+
+    pub fn set_sie(&mut self, sie: bool) {
+        set_bit(&mut self.0, 2, sie);
+    }
+
+    pub fn aie(&self) -> bool {
+        bit(self.0, 1)
+    }
+
+    pub fn set_aie(&mut self, aie: bool) {
+        set_bit(&mut self.0, 1, aie);
+    }
+
+    pub fn cie(&self) -> bool {
+        bit(self.0, 0)
+    }
+
+    pub fn set_cie(&mut self, cie: bool) {
+        set_bit(&mut self.0, 0, cie);
+    }
+}
+impl From<u8> for Control1 {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Control2(u8);
+impl Control2 {
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    // read-only, cleared by reading Control_2
+    pub fn wtaf(&self) -> bool {
+        bit(self.0, 7)
+    }
+
+    pub fn ctaf(&self) -> bool {
+        bit(self.0, 6)
+    }
+
+    pub fn set_ctaf(&mut self, ctaf: bool) {
+        set_bit(&mut self.0, 6, ctaf);
+    }
+
+    pub fn ctbf(&self) -> bool {
+        bit(self.0, 5)
+    }
+
+    pub fn set_ctbf(&mut self, ctbf: bool) {
+        set_bit(&mut self.0, 5, ctbf);
+    }
+
+    pub fn sf(&self) -> bool {
+        bit(self.0, 4)
+    }
+
+    pub fn set_sf(&mut self, sf: bool) {
+        set_bit(&mut self.0, 4, sf);
+    }
+
+    pub fn af(&self) -> bool {
+        bit(self.0, 3)
+    }
+
+    pub fn set_af(&mut self, af: bool) {
+        set_bit(&mut self.0, 3, af);
+    }
+
+    pub fn wtaie(&self) -> bool {
+        bit(self.0, 2)
+    }
+
+    pub fn set_wtaie(&mut self, wtaie: bool) {
+        set_bit(&mut self.0, 2, wtaie);
+    }
+
+    pub fn ctaie(&self) -> bool {
+        bit(self.0, 1)
+    }
+
+    pub fn set_ctaie(&mut self, ctaie: bool) {
+        set_bit(&mut self.0, 1, ctaie);
+    }
+
+    pub fn ctbie(&self) -> bool {
+        bit(self.0, 0)
+    }
+
+    pub fn set_ctbie(&mut self, ctbie: bool) {
+        set_bit(&mut self.0, 0, ctbie);
+    }
+}
+impl From<u8> for Control2 {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Control3(u8);
+impl Control3 {
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    // None for the undocumented patterns 0b011 and 0b110
+    pub fn pm(&self) -> Option<PowerManagement> {
+        PowerManagement::try_from(get_bits(self.0, 3, 5)).ok()
+    }
+
+    pub fn set_pm(&mut self, pm: PowerManagement) {
+        set_bits(&mut self.0, pm as u8, 5, 0b1110_0000);
+    }
+
+    pub fn bsf(&self) -> bool {
+        bit(self.0, 3)
+    }
+
+    pub fn set_bsf(&mut self, bsf: bool) {
+        set_bit(&mut self.0, 3, bsf);
+    }
+
+    // read-only
+    pub fn blf(&self) -> bool {
+        bit(self.0, 2)
+    }
+
+    pub fn bsie(&self) -> bool {
+        bit(self.0, 1)
+    }
+
+    pub fn set_bsie(&mut self, bsie: bool) {
+        set_bit(&mut self.0, 1, bsie);
+    }
+
+    pub fn blie(&self) -> bool {
+        bit(self.0, 0)
+    }
+
+    pub fn set_blie(&mut self, blie: bool) {
+        set_bit(&mut self.0, 0, blie);
+    }
+}
+impl From<u8> for Control3 {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+fn bit(byte: u8, offset: u8) -> bool {
+    get_bits(byte, 1, offset) == 1
+}
+
+fn set_bit(byte: &mut u8, offset: u8, value: bool) {
+    set_bits(byte, value as u8, offset, 1 << offset);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control1_getters() {
+        let control = Control1::from(0b1010_1010);
+        assert!(control.cap_sel());
+        assert!(!control.t());
+        assert!(control.stop());
+        assert!(!control.sr());
+        assert!(control.hour_mode_12hr());
+        assert!(!control.sie());
+        assert!(control.aie());
+        assert!(!control.cie());
+    }
+
+    #[test]
+    fn control1_setters() {
+        let mut control = Control1::default();
+        control.set_stop(true);
+        control.set_cie(true);
+        assert_eq!(control.bits(), 0b0010_0001);
+        control.set_stop(false);
+        assert_eq!(control.bits(), 0b0000_0001);
+    }
+
+    #[test]
+    fn control2_flags() {
+        let mut control = Control2::from(0b1101_1000);
+        assert!(control.wtaf());
+        assert!(control.ctaf());
+        assert!(!control.ctbf());
+        assert!(control.sf());
+        assert!(control.af());
+        control.set_af(false);
+        control.set_ctbie(true);
+        assert_eq!(control.bits(), 0b1101_0001);
+    }
+
+    #[test]
+    fn control3_pm() {
+        let mut control = Control3::from(0b0110_0101);
+        assert_eq!(control.pm(), None);
+        assert!(control.blf());
+        assert!(control.blie());
+        control.set_pm(PowerManagement::SwitchOverDisabledLowDetectionDisabled);
+        assert_eq!(control.pm(), Some(PowerManagement::SwitchOverDisabledLowDetectionDisabled));
+        assert_eq!(control.bits(), 0b1110_0101);
+    }
+}
@@ -1,6 +1,8 @@
+use core::cmp::Ordering;
 use crate::bits::{decode_bcd, encode_bcd};
+use crate::typedefs::Hours;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DayOfWeek {
     Sunday = 0x0,
     Monday = 0x1,
@@ -26,7 +28,7 @@ impl TryFrom<u8> for DayOfWeek {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Month {
     January = 0x1,
     February = 0x2,
@@ -62,10 +64,32 @@ impl TryFrom<u8> for Month {
     }
 }
 
+// first year of the 100 year span covered by the 2-digit year register
+pub const DEFAULT_CENTURY: u16 = 2000;
+
+// the field that failed validation, each is rejected for being outside the range the chip stores
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateTimeError {
+    // above 59
+    InvalidSeconds,
+    // above 59
+    InvalidMinutes,
+    // above 23
+    InvalidHours,
+    // zero or past the last day of the month
+    InvalidDay,
+    // not a DayOfWeek
+    InvalidWeekday,
+    // not a Month
+    InvalidMonth,
+    // above 99
+    InvalidYear,
+}
+
 // TODO might want an explicit BCD-encoded version?
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct DateTime {
-    pub seconds: u8,
+    pub(crate) seconds: u8,
     pub(crate) minutes: u8,
     pub(crate) hours: u8,
     pub(crate) day: u8,
@@ -73,14 +97,15 @@ pub struct DateTime {
     pub(crate) month: u8,
     pub(crate) year: u8,
 }
+// 2000-01-01 00:00:00, a Saturday
 impl Default for DateTime {
     fn default() -> Self {
         Self {
             seconds: 0,
             minutes: 0,
             hours: 0,
-            day: 0,
-            day_of_week: 0,
+            day: 1,
+            day_of_week: DayOfWeek::Saturday as u8,
             month: 1,
             year: 0
         }
@@ -96,15 +121,8 @@ impl DateTime {
         day_of_week: DayOfWeek,
         month: Month,
         year: u8,
-    ) -> Option<Self> {
-        // TODO more useful errors in Result<> instead of Option<>?
-        if seconds > 59 { return None; }
-        if minutes > 59 { return None; }
-        if hours > 23 { return None; }
-        if day > 31 { return None; }
-        if year > 99 { return None; }
-
-        Some(Self {
+    ) -> Result<Self, DateTimeError> {
+        let dt = Self {
             seconds,
             minutes,
             hours,
@@ -112,56 +130,365 @@ impl DateTime {
             day_of_week: day_of_week as u8,
             month: month as u8,
             year,
-        })
+        };
+        dt.validate()?;
+        Ok(dt)
     }
 
-    pub(crate) fn decode(&self) -> Self {
-        Self {
-            seconds: decode_bcd(self.seconds),
-            minutes: decode_bcd(self.minutes),
-            hours: decode_bcd(self.hours),
-            day: decode_bcd(self.day),
-            day_of_week: decode_bcd(self.day_of_week),
-            month: decode_bcd(self.month),
-            year: decode_bcd(self.year),
+    // day_of_week is derived from the date, century is the first year of the span the 2-digit year
+    // is relative to, e.g. DEFAULT_CENTURY
+    pub fn from_calendar_date(
+        seconds: u8,
+        minutes: u8,
+        hours: u8,
+        day: u8,
+        month: Month,
+        year: u8,
+        century: u16,
+    ) -> Result<Self, DateTimeError> {
+        let month = month as u8;
+        if year > 99 { return Err(DateTimeError::InvalidYear); }
+        let dt = Self {
+            seconds,
+            minutes,
+            hours,
+            day,
+            day_of_week: weekday(century as u32 + year as u32, month, day),
+            month,
+            year,
+        };
+        dt.validate()?;
+        Ok(dt)
+    }
+
+    pub fn seconds(&self) -> u8 {
+        self.seconds
+    }
+
+    pub fn minutes(&self) -> u8 {
+        self.minutes
+    }
+
+    // always in 24-hour format
+    pub fn hours(&self) -> u8 {
+        self.hours
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    // fails for an impossible value read back from the chip
+    pub fn day_of_week(&self) -> Result<DayOfWeek, DateTimeError> {
+        DayOfWeek::try_from(self.day_of_week).map_err(|_| DateTimeError::InvalidWeekday)
+    }
+
+    // fails for an impossible value read back from the chip
+    pub fn month(&self) -> Result<Month, DateTimeError> {
+        Month::try_from(self.month).map_err(|_| DateTimeError::InvalidMonth)
+    }
+
+    pub fn year(&self) -> u8 {
+        self.year
+    }
+
+    // the setters revalidate the whole value and leave it unchanged on error
+    pub fn set_seconds(&mut self, seconds: u8) -> Result<(), DateTimeError> {
+        self.update(|dt| dt.seconds = seconds)
+    }
+
+    pub fn set_minutes(&mut self, minutes: u8) -> Result<(), DateTimeError> {
+        self.update(|dt| dt.minutes = minutes)
+    }
+
+    pub fn set_hours(&mut self, hours: u8) -> Result<(), DateTimeError> {
+        self.update(|dt| dt.hours = hours)
+    }
+
+    pub fn set_day(&mut self, day: u8) -> Result<(), DateTimeError> {
+        self.update(|dt| dt.day = day)
+    }
+
+    pub fn set_day_of_week(&mut self, day_of_week: DayOfWeek) -> Result<(), DateTimeError> {
+        self.update(|dt| dt.day_of_week = day_of_week as u8)
+    }
+
+    pub fn set_month(&mut self, month: Month) -> Result<(), DateTimeError> {
+        self.update(|dt| dt.month = month as u8)
+    }
+
+    pub fn set_year(&mut self, year: u8) -> Result<(), DateTimeError> {
+        self.update(|dt| dt.year = year)
+    }
+
+    fn update(&mut self, change: impl FnOnce(&mut Self)) -> Result<(), DateTimeError> {
+        let mut dt = *self;
+        change(&mut dt);
+        dt.validate()?;
+        *self = dt;
+        Ok(())
+    }
+
+    // checked by set_datetime before anything is written, values read back after a power loss can fail
+    pub fn validate(&self) -> Result<(), DateTimeError> {
+        if self.seconds > 59 { return Err(DateTimeError::InvalidSeconds); }
+        if self.minutes > 59 { return Err(DateTimeError::InvalidMinutes); }
+        if self.hours > 23 { return Err(DateTimeError::InvalidHours); }
+        if !(1..=12).contains(&self.month) { return Err(DateTimeError::InvalidMonth); }
+        if self.year > 99 { return Err(DateTimeError::InvalidYear); }
+        if !(1..=days_in_month(self.month, self.year)).contains(&self.day) { return Err(DateTimeError::InvalidDay); }
+        if self.day_of_week > 6 { return Err(DateTimeError::InvalidWeekday); }
+        Ok(())
+    }
+
+    // the chip never checks day_of_week against the date, so a wrong one is stored as is
+    pub fn validate_weekday(&self, century: u16) -> Result<(), DateTimeError> {
+        self.validate()?;
+        if self.day_of_week != weekday(century as u32 + self.year as u32, self.month, self.day) {
+            return Err(DateTimeError::InvalidWeekday);
         }
+        Ok(())
     }
 
-    pub(crate) fn encode(&self) -> Self {
+    // seconds since 2000-01-01 00:00:00 for a decoded 24-hour value
+    pub(crate) fn seconds_since_2000(&self) -> u32 {
+        const DAYS_BEFORE_MONTH: [u32; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+        let year = self.year as u32;
+        let month = self.month.clamp(1, 12) as u32;
+        // every year divisible by 4 in 2000-2099 is a leap year
+        let mut days = year * 365 + year.div_ceil(4) + DAYS_BEFORE_MONTH[month as usize - 1] + self.day as u32;
+        if month > 2 && year.is_multiple_of(4) { days += 1; }
+        days = days.saturating_sub(1);
+        ((days * 24 + self.hours as u32) * 60 + self.minutes as u32) * 60 + self.seconds as u32
+    }
+
+    // Seconds to Years register values in that order, hours are always held in 24-hour format and
+    // mode_12hr is the 12_24 bit of CONTROL_1. Unused bits and the OS flag are masked out.
+    pub(crate) fn decode(regs: [u8; 7], mode_12hr: bool) -> Self {
+        let [seconds, minutes, hours, day, day_of_week, month, year] = regs;
         Self {
-            seconds: encode_bcd(self.seconds),
-            minutes: encode_bcd(self.minutes),
-            hours: encode_bcd(self.hours),
-            day: encode_bcd(self.day),
-            day_of_week: encode_bcd(self.day_of_week),
-            month: encode_bcd(self.month),
-            year: encode_bcd(self.year),
+            seconds: decode_bcd(seconds & 0b111_1111),
+            minutes: decode_bcd(minutes & 0b111_1111),
+            hours: Hours::decode(hours, mode_12hr).to_24_hour(),
+            day: decode_bcd(day & 0b11_1111),
+            day_of_week: decode_bcd(day_of_week & 0b111),
+            month: decode_bcd(month & 0b1_1111),
+            year: decode_bcd(year),
         }
     }
+
+    // writing the seconds register with OS clear resets the oscillator stop flag
+    pub(crate) fn encode(&self, mode_12hr: bool) -> [u8; 7] {
+        [
+            encode_bcd(self.seconds) & 0b111_1111,
+            encode_bcd(self.minutes),
+            Hours::from_24_hour(self.hours, mode_12hr).encode(),
+            encode_bcd(self.day),
+            encode_bcd(self.day_of_week),
+            encode_bcd(self.month),
+            encode_bcd(self.year),
+        ]
+    }
 }
 
+// chronological, day_of_week only breaks ties between values that disagree with their date
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.year, self.month, self.day, self.hours, self.minutes, self.seconds, self.day_of_week)
+            .cmp(&(other.year, other.month, other.day, other.hours, other.minutes, other.seconds, other.day_of_week))
+    }
+}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// month is 1-12 and year the 2-digit register value, the chip treats every year divisible by 4 as a
+// leap year which holds for 2000-2099
+pub(crate) fn days_in_month(month: u8, year: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// full year for the 2-digit register value, century is the first year of the 100 year span stored by
+// the chip, e.g. DEFAULT_CENTURY. Registers read back after a power loss can hold a year above 99, so
+// every conversion out of DateTime is fallible.
+#[cfg(any(feature = "chrono", feature = "jiff", feature = "time"))]
+pub(crate) fn full_year(year: u8, century: u16) -> Result<i32, DateTimeError> {
+    if year > 99 { return Err(DateTimeError::InvalidYear); }
+    Ok(century as i32 + year as i32)
+}
+
+// inverse of full_year, years outside the span starting at century can't be stored
+#[cfg(any(feature = "chrono", feature = "jiff", feature = "time"))]
+pub(crate) fn year_in_century(full_year: i32, century: u16) -> Result<u8, DateTimeError> {
+    let year = full_year - century as i32;
+    if !(0..100).contains(&year) { return Err(DateTimeError::InvalidYear); }
+    Ok(year as u8)
+}
+
+// Sakamoto's method for the proleptic Gregorian calendar, 0 is Sunday as in DayOfWeek
+fn weekday(year: u32, month: u8, day: u8) -> u8 {
+    const MONTH_OFFSETS: [u32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    // the calendar repeats every 400 years, adding one cycle keeps year 0 from underflowing
+    let year = year + 400 - (month < 3) as u32;
+    let month = month.clamp(1, 12) as usize;
+    ((year + year / 4 - year / 100 + year / 400 + MONTH_OFFSETS[month - 1] + day as u32) % 7) as u8
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_new_invalid_seconds_none() {}
+    fn test_new_invalid_seconds_err() {
+        assert_eq!(DateTime::new(60, 0, 0, 1, DayOfWeek::Sunday, Month::January, 0).unwrap_err(), DateTimeError::InvalidSeconds);
+    }
+
+    #[test]
+    fn test_new_invalid_minutes_err() {
+        assert_eq!(DateTime::new(0, 60, 0, 1, DayOfWeek::Sunday, Month::January, 0).unwrap_err(), DateTimeError::InvalidMinutes);
+    }
+
+    #[test]
+    fn test_new_invalid_hours_err() {
+        assert_eq!(DateTime::new(0, 0, 24, 1, DayOfWeek::Sunday, Month::January, 0).unwrap_err(), DateTimeError::InvalidHours);
+    }
+
+    #[test]
+    fn test_new_invalid_day_err() {
+        assert_eq!(DateTime::new(0, 0, 0, 32, DayOfWeek::Sunday, Month::January, 0).unwrap_err(), DateTimeError::InvalidDay);
+        assert_eq!(DateTime::new(0, 0, 0, 0, DayOfWeek::Sunday, Month::January, 0).unwrap_err(), DateTimeError::InvalidDay);
+        assert_eq!(DateTime::new(0, 0, 0, 31, DayOfWeek::Sunday, Month::April, 0).unwrap_err(), DateTimeError::InvalidDay);
+    }
 
     #[test]
-    fn test_new_invalid_minutes_none() {}
+    fn test_new_leap_day() {
+        assert!(DateTime::new(0, 0, 0, 29, DayOfWeek::Thursday, Month::February, 24).is_ok());
+        assert_eq!(DateTime::new(0, 0, 0, 29, DayOfWeek::Sunday, Month::February, 25).unwrap_err(), DateTimeError::InvalidDay);
+    }
 
     #[test]
-    fn test_new_invalid_hours_none() {}
+    fn test_from_calendar_date_ok() {
+        let dt = DateTime::from_calendar_date(0, 0, 0, 1, Month::January, 25, DEFAULT_CENTURY).unwrap();
+        assert_eq!(dt.day_of_week, DayOfWeek::Wednesday as u8);
+        let dt = DateTime::from_calendar_date(0, 0, 0, 29, Month::February, 24, DEFAULT_CENTURY).unwrap();
+        assert_eq!(dt.day_of_week, DayOfWeek::Thursday as u8);
+        let dt = DateTime::from_calendar_date(0, 0, 0, 1, Month::January, 0, 2100).unwrap();
+        assert_eq!(dt.day_of_week, DayOfWeek::Friday as u8);
+        let dt = DateTime::from_calendar_date(0, 0, 0, 1, Month::January, 0, 0).unwrap();
+        assert_eq!(dt.day_of_week, DayOfWeek::Saturday as u8);
+    }
 
     #[test]
-    fn test_new_invalid_day_none() {}
+    fn test_from_calendar_date_invalid_err() {
+        assert_eq!(DateTime::from_calendar_date(0, 0, 0, 1, Month::January, 100, DEFAULT_CENTURY).unwrap_err(), DateTimeError::InvalidYear);
+        assert_eq!(DateTime::from_calendar_date(0, 0, 0, 31, Month::June, 25, DEFAULT_CENTURY).unwrap_err(), DateTimeError::InvalidDay);
+    }
 
     #[test]
-    fn test_new_invalid_year_none() {}
+    fn test_new_invalid_year_err() {
+        assert_eq!(DateTime::new(0, 0, 0, 1, DayOfWeek::Sunday, Month::January, 100).unwrap_err(), DateTimeError::InvalidYear);
+    }
+
+    #[cfg(any(feature = "chrono", feature = "jiff", feature = "time"))]
+    #[test]
+    fn test_full_year() {
+        assert_eq!(full_year(25, DEFAULT_CENTURY), Ok(2025));
+        assert_eq!(full_year(99, u16::MAX), Ok(u16::MAX as i32 + 99));
+        assert_eq!(full_year(100, DEFAULT_CENTURY), Err(DateTimeError::InvalidYear));
+    }
+
+    #[cfg(any(feature = "chrono", feature = "jiff", feature = "time"))]
+    #[test]
+    fn test_year_in_century() {
+        assert_eq!(year_in_century(2199, 2100), Ok(99));
+        assert_eq!(year_in_century(2199, DEFAULT_CENTURY), Err(DateTimeError::InvalidYear));
+        assert_eq!(year_in_century(1999, DEFAULT_CENTURY), Err(DateTimeError::InvalidYear));
+    }
+
+    #[test]
+    fn test_getters() {
+        let dt = DateTime::new(59, 15, 2, 11, DayOfWeek::Wednesday, Month::October, 45).unwrap();
+        assert_eq!((dt.seconds(), dt.minutes(), dt.hours(), dt.day(), dt.year()), (59, 15, 2, 11, 45));
+        assert_eq!(dt.day_of_week(), Ok(DayOfWeek::Wednesday));
+        assert_eq!(dt.month(), Ok(Month::October));
+        let dt = DateTime::decode([0, 0, 0, 1, 0b111, 0b1_0011, 0], false);
+        assert_eq!(dt.day_of_week(), Err(DateTimeError::InvalidWeekday));
+        assert_eq!(dt.month(), Err(DateTimeError::InvalidMonth));
+    }
+
+    #[test]
+    fn test_ord() {
+        let earlier = DateTime::new(59, 59, 23, 31, DayOfWeek::Tuesday, Month::December, 24).unwrap();
+        let later = DateTime::new(0, 0, 0, 1, DayOfWeek::Wednesday, Month::January, 25).unwrap();
+        assert!(earlier < later);
+        assert_eq!(earlier.max(later), later);
+    }
+
+    #[test]
+    fn test_setters_revalidate() {
+        let mut dt = DateTime::new(0, 0, 0, 31, DayOfWeek::Friday, Month::January, 25).unwrap();
+        assert_eq!(dt.set_month(Month::February), Err(DateTimeError::InvalidDay));
+        assert_eq!(dt.set_seconds(60), Err(DateTimeError::InvalidSeconds));
+        assert_eq!(dt, DateTime::new(0, 0, 0, 31, DayOfWeek::Friday, Month::January, 25).unwrap());
+        dt.set_day(28).unwrap();
+        dt.set_month(Month::February).unwrap();
+        dt.set_hours(23).unwrap();
+        assert_eq!((dt.day(), dt.month(), dt.hours()), (28, Ok(Month::February), 23));
+    }
+
+    #[test]
+    fn test_validate_weekday() {
+        let dt = DateTime::new(0, 0, 0, 1, DayOfWeek::Tuesday, Month::January, 25).unwrap();
+        assert_eq!(dt.validate_weekday(DEFAULT_CENTURY).unwrap_err(), DateTimeError::InvalidWeekday);
+        let dt = DateTime::new(0, 0, 0, 1, DayOfWeek::Wednesday, Month::January, 25).unwrap();
+        assert!(dt.validate_weekday(DEFAULT_CENTURY).is_ok());
+        assert!(DateTime::default().validate_weekday(DEFAULT_CENTURY).is_ok());
+    }
+
+    #[test]
+    fn test_validate_decoded_err() {
+        let dt = DateTime::decode([0, 0, 0, 1, 0b111, 0b1_0011, 0], false);
+        assert_eq!(dt.validate().unwrap_err(), DateTimeError::InvalidMonth);
+        assert!(DateTime::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_seconds_since_2000() {
+        let epoch = DateTime::new(0, 0, 0, 1, DayOfWeek::Saturday, Month::January, 0).unwrap();
+        assert_eq!(epoch.seconds_since_2000(), 0);
+        let leap_day = DateTime::new(0, 0, 0, 1, DayOfWeek::Wednesday, Month::March, 0).unwrap();
+        assert_eq!(leap_day.seconds_since_2000(), 60 * 86_400);
+        let dt = DateTime::new(5, 4, 3, 1, DayOfWeek::Monday, Month::January, 1).unwrap();
+        assert_eq!(dt.seconds_since_2000(), 366 * 86_400 + 3 * 3600 + 4 * 60 + 5);
+    }
+
+    #[test]
+    fn test_new_ok() {
+        let dt = DateTime::new(59, 15, 2, 11, DayOfWeek::Wednesday, Month::October, 45).unwrap();
+        assert_eq!(dt.seconds, 59);
+        assert_eq!(dt.month, 10);
+        assert_eq!(dt.encode(false)[0], 0b101_1001);
+    }
+
+    #[test]
+    fn test_encode_12_hour_mode() {
+        let dt = DateTime::new(0, 0, 13, 1, DayOfWeek::Sunday, Month::January, 0).unwrap();
+        assert_eq!(dt.encode(false)[2], 0b1_0011);
+        assert_eq!(dt.encode(true)[2], 0b10_0001);
+        assert_eq!(DateTime::decode(dt.encode(true), true).hours, 13);
+    }
 
     #[test]
-    fn test_new_some() {
-        // validate bcd-encoded
+    fn test_decode_masks_oscillator_stop() {
+        let dt = DateTime::decode([0b1101_1001, 0, 0, 1, 0, 1, 0], false);
+        assert_eq!(dt.seconds, 59);
     }
 }
\ No newline at end of file
@@ -1,213 +1,645 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{Error, InputPin};
 use embedded_hal::i2c::{I2c, Operation};
-use crate::typedefs::{Pcf8523Error, Pcf8523Interrupt, PowerManagement};
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs as AsyncDelayNs;
+#[cfg(feature = "async")]
+use embedded_hal_async::digital::Wait;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+use crate::alarm::Alarm;
+use crate::cache::RegisterCache;
+use crate::calibration::{Calibration, DriftMeasurement};
+use crate::control::{Control1, Control2, Control3};
+use crate::typedefs::{BatteryStatus, ClockOutFrequency, Hours, Pcf8523Error, Pcf8523Interrupt, Pcf8523Interrupts, PowerManagement, TimeStatus};
 use crate::bits::{decode_bcd, get_bits, set_bits};
 use crate::datetime::DateTime;
-use crate::registers::{PCF8523_CONTROL_1, PCF8523_CONTROL_2, PCF8523_CONTROL_3, PCF8523_DAYS, PCF8523_HOURS, PCF8523_MINUTES, PCF8523_MONTHS, PCF8523_SECONDS, PCF8523_WEEKDAYS, PCF8523_YEARS};
+use crate::offset::{Offset, OffsetMode};
+use crate::timer::{TimerAMode, TimerBPulseWidth, TimerInterruptMode, TimerSourceClock};
+use crate::registers::{PCF8523_CONTROL_1, PCF8523_CONTROL_2, PCF8523_CONTROL_3, PCF8523_DAYS, PCF8523_HOURS, PCF8523_HOUR_ALARM, PCF8523_MINUTES, PCF8523_MINUTE_ALARM, PCF8523_MONTHS, PCF8523_OFFSET, PCF8523_SECONDS, PCF8523_TMR_A_FREQ_CTRL, PCF8523_TMR_A_REG, PCF8523_TMR_B_FREQ_CTRL, PCF8523_TMR_B_REG, PCF8523_TMR_CLKOUT_CTRL, PCF8523_WEEKDAYS, PCF8523_WEEKDAY_ALARM, PCF8523_YEARS};
 
 pub const PCF8523_I2C_ADDRESS: u8 = 0x68;
 
+// writing this to CONTROL_1 resets every register
+const SOFTWARE_RESET: u8 = 0b101_1000;
+// the rest of the second after the first increment, which follows 0.507813 s to 0.507935 s after STOP
+// is cleared, so releasing this long after a reference edge puts the increment on the next edge
+const SYNCHRONIZED_RELEASE_DELAY_US: u32 = 492_126;
+
+#[maybe_async_cfg::maybe(
+    idents(Pcf8523(sync = "Pcf8523", async = "Pcf8523Async"), I2c(sync = "I2c", async = "AsyncI2c")),
+    sync(keep_self),
+    async(feature = "async"),
+)]
 pub struct Pcf8523<I2C> {
-    i2c: I2C
+    i2c: I2C,
+    // shadow copies of registers only the driver writes, None while caching is disabled
+    cache: Option<RegisterCache>,
 }
 
+#[maybe_async_cfg::maybe(
+    idents(
+        Pcf8523(sync = "Pcf8523", async = "Pcf8523Async"),
+        I2c(sync = "I2c", async = "AsyncI2c"),
+        InputPin(sync = "InputPin", async = "Wait"),
+        FnOnce(sync = "FnOnce", async = "AsyncFnOnce"),
+        DelayNs(sync = "DelayNs", async = "AsyncDelayNs"),
+    ),
+    sync(keep_self),
+    async(feature = "async"),
+)]
 impl<I2C: I2c> Pcf8523<I2C> {
     pub fn new(i2c: I2C) -> Self {
-        Self { i2c }
+        Self { i2c, cache: None }
+    }
+
+    // computes the offset correcting the measured drift on top of the offset currently applied and writes it
+    pub async fn calibrate(&mut self, measurement: DriftMeasurement, mode: OffsetMode) -> Result<Calibration, Pcf8523Error<I2C::Error>> {
+        let calibration = Calibration::new(self.get_offset().await?, mode, measurement);
+        self.set_offset(calibration.offset()).await?;
+        Ok(calibration)
     }
 
-    pub fn clear_interrupt(&mut self, interrupt: Pcf8523Interrupt) -> Result<(), Pcf8523Error<I2C::Error>> {
+    pub async fn clear_interrupt(&mut self, interrupt: Pcf8523Interrupt) -> Result<(), Pcf8523Error<I2C::Error>> {
         match interrupt {
             Pcf8523Interrupt::Alarm => {
-                self.clear_alarm_interrupt()
+                self.clear_alarm_interrupt().await
             }
+            // BLF is read-only and clears once the battery voltage recovers
+            Pcf8523Interrupt::BatteryLow => Ok(()),
             Pcf8523Interrupt::BatterySwitchOver => {
-                self.clear_bsf_interrupt()
+                self.clear_bsf_interrupt().await
             }
             Pcf8523Interrupt::CountdownTimerA => {
-                self.clear_countdown_timer_a_interrupt()
+                self.clear_countdown_timer_a_interrupt().await
             }
             Pcf8523Interrupt::CountdownTimerB => {
-                self.clear_countdown_timer_b_interrupt()
+                self.clear_countdown_timer_b_interrupt().await
             }
             Pcf8523Interrupt::Second => {
-                self.clear_second_interrupt()
+                self.clear_second_interrupt().await
             }
             Pcf8523Interrupt::WatchdogTimerA => {
-                self.clear_watchdog_timer_a_interrupt()
+                self.clear_watchdog_timer_a_interrupt().await
             }
         }
     }
 
-    fn clear_alarm_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_2)?;
+    // reads the control registers in one access and clears the flags of every interrupt in the set in one write
+    pub async fn clear_interrupts(&mut self, interrupts: Pcf8523Interrupts) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut controls = self.read_control_regs().await?;
+        interrupts.clear_flags(&mut controls);
+        self.i2c.write(PCF8523_I2C_ADDRESS, &[PCF8523_CONTROL_2, controls[1], controls[2]]).await.map_err(Pcf8523Error::I2C)
+    }
+
+    // clears OS by writing the seconds register back, the clock is stopped around the read and write so
+    // a tick can't be undone. The time stays within the current second.
+    pub async fn clear_oscillator_stop_flag(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let control_1 = self.read_reg(PCF8523_CONTROL_1).await?;
+        let mut stopped = control_1;
+        set_bits(&mut stopped, 1, 5, 0b10_0000);
+        self.write_reg(PCF8523_CONTROL_1, stopped).await?;
+        let written = match self.read_reg(PCF8523_SECONDS).await {
+            Ok(mut seconds) => {
+                set_bits(&mut seconds, 0, 7, 0b1000_0000);
+                self.write_reg(PCF8523_SECONDS, seconds).await
+            }
+            Err(e) => Err(e),
+        };
+        let restored = self.write_reg(PCF8523_CONTROL_1, control_1).await;
+        written.and(restored)
+    }
+
+    // the other flags are written as 1 so none raised since the read is cleared
+    async fn clear_alarm_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_2).await? | Pcf8523Interrupts::clearable_flags(PCF8523_CONTROL_2);
         set_bits(&mut val, 0, 3, 0b1000);
-        self.write_reg(PCF8523_CONTROL_2, val)?;
+        self.write_reg(PCF8523_CONTROL_2, val).await?;
         Ok(())
     }
 
-    fn clear_bsf_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_3)?;
-        set_bits(&mut val, 0, 3, 0b100);
-        self.write_reg(PCF8523_CONTROL_3, val)?;
+    async fn clear_bsf_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_3).await?;
+        set_bits(&mut val, 0, 3, 0b1000);
+        self.write_reg(PCF8523_CONTROL_3, val).await?;
         Ok(())
     }
 
-    fn clear_countdown_timer_a_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_2)?;
+    async fn clear_countdown_timer_a_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_2).await?;
         set_bits(&mut val, 0, 6, 0b100_0000);
-        self.write_reg(PCF8523_CONTROL_2, val)?;
+        self.write_reg(PCF8523_CONTROL_2, val).await?;
         Ok(())
     }
 
-    fn clear_countdown_timer_b_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_2)?;
+    async fn clear_countdown_timer_b_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_2).await?;
         set_bits(&mut val, 0, 5, 0b10_0000);
-        self.write_reg(PCF8523_CONTROL_2, val)?;
+        self.write_reg(PCF8523_CONTROL_2, val).await?;
         Ok(())
     }
 
-    fn clear_second_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_2)?;
+    async fn clear_second_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_2).await?;
         set_bits(&mut val, 0, 4, 0b1_0000);
-        self.write_reg(PCF8523_CONTROL_2, val)?;
+        self.write_reg(PCF8523_CONTROL_2, val).await?;
         Ok(())
     }
 
-    fn clear_watchdog_timer_a_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
-        self.read_reg(PCF8523_CONTROL_2)?;
+    async fn clear_watchdog_timer_a_interrupt(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.read_reg(PCF8523_CONTROL_2).await?;
         Ok(())
     }
 
-    pub fn freeze_rtc_time_circuits(&mut self, freeze: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_1)?;
+    // sets AEN_M, AEN_H, AEN_D and AEN_W in one write, then clears AIE
+    pub async fn disable_alarm(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut control_1 = self.read_reg(PCF8523_CONTROL_1).await?;
+        set_bits(&mut control_1, 0, 1, 0b10);
+        self.i2c.write(PCF8523_I2C_ADDRESS, &[PCF8523_MINUTE_ALARM, 0b1000_0000, 0b1000_0000, 0b1000_0000, 0b1000_0000])
+            .await.map_err(Pcf8523Error::I2C)?;
+        self.write_reg(PCF8523_CONTROL_1, control_1).await
+    }
+
+    pub fn disable_cache(&mut self) {
+        self.cache = None;
+    }
+
+    // caches CONTROL_1, Tmr_CLKOUT_ctrl, Tmr_A_freq_ctrl and Tmr_B_freq_ctrl so read-modify-write methods only
+    // write, registers are read once here and written through afterwards
+    pub async fn enable_cache(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.cache = Some(RegisterCache::default());
+        let mut control_1 = [0u8];
+        let mut timers = [0u8; 5];
+        self.i2c.transaction(PCF8523_I2C_ADDRESS, &mut [
+            Operation::Write(&[PCF8523_CONTROL_1]), Operation::Read(&mut control_1),
+            Operation::Write(&[PCF8523_TMR_CLKOUT_CTRL]), Operation::Read(&mut timers),
+        ]).await.map_err(Pcf8523Error::I2C)?;
+        self.update_cache(PCF8523_CONTROL_1, &control_1);
+        self.update_cache(PCF8523_TMR_CLKOUT_CTRL, &timers);
+        Ok(())
+    }
+
+    pub async fn freeze_rtc_time_circuits(&mut self, freeze: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_1).await?;
         set_bits(&mut val, freeze as u8, 5, 0b10_0000);
-        self.write_reg(PCF8523_CONTROL_1, val)?;
+        self.write_reg(PCF8523_CONTROL_1, val).await?;
         Ok(())
     }
 
-    pub fn get_battery_status(&mut self) -> Result<bool, Pcf8523Error<I2C::Error>> {
-        Ok((self.read_reg(PCF8523_CONTROL_3)? >> 2) & 0b1 == 1)
+    pub async fn get_alarm(&mut self) -> Result<Alarm, Pcf8523Error<I2C::Error>> {
+        let mode_12hr = self.get_hour_mode_12hr().await?;
+        let mut regs = [0u8; 4];
+        self.read_regs(PCF8523_MINUTE_ALARM, &mut regs).await?;
+        Alarm::decode(regs, mode_12hr).ok_or(Pcf8523Error::InvalidRegisterValue(PCF8523_WEEKDAY_ALARM))
     }
 
-    pub fn get_clock_integrity_guaranteed(&mut self) -> Result<bool, Pcf8523Error<I2C::Error>> {
-        Ok(get_bits(self.read_reg(PCF8523_SECONDS)?, 1, 7) == 0)
+    pub async fn get_battery_status(&mut self) -> Result<BatteryStatus, Pcf8523Error<I2C::Error>> {
+        Ok(BatteryStatus::decode(self.read_reg(PCF8523_CONTROL_3).await?))
     }
 
-    pub fn get_correction_interrupt_generated(&mut self) -> Result<bool, Pcf8523Error<I2C::Error>> {
-        Ok(get_bits(self.read_reg(PCF8523_CONTROL_1)?, 1, 0) == 1)
+    pub async fn get_clkout(&mut self) -> Result<ClockOutFrequency, Pcf8523Error<I2C::Error>> {
+        Ok(ClockOutFrequency::from(get_bits(self.read_reg(PCF8523_TMR_CLKOUT_CTRL).await?, 3, 3)))
+    }
+
+    pub async fn get_clock_integrity_guaranteed(&mut self) -> Result<bool, Pcf8523Error<I2C::Error>> {
+        Ok(get_bits(self.read_reg(PCF8523_SECONDS).await?, 1, 7) == 0)
+    }
+
+    // CIE, when set INT1 pulses each time the offset register applies a correction
+    pub async fn get_correction_interrupt_enabled(&mut self) -> Result<bool, Pcf8523Error<I2C::Error>> {
+        Ok(get_bits(self.read_reg(PCF8523_CONTROL_1).await?, 1, 0) == 1)
+    }
+
+    #[deprecated(note = "CIE is an interrupt enable, use get_correction_interrupt_enabled")]
+    pub async fn get_correction_interrupt_generated(&mut self) -> Result<bool, Pcf8523Error<I2C::Error>> {
+        self.get_correction_interrupt_enabled().await
     }
 
     // 8.6.8
-    // use an i2c transaction to complete accesses in < 1 second and avoid corruption
-    pub fn get_datetime(&mut self) -> Result<DateTime, Pcf8523Error<I2C::Error>> {
-        let mut dt = DateTime::default();
-        self.i2c.transaction(PCF8523_I2C_ADDRESS, &mut [
-            Operation::Write(&[PCF8523_SECONDS]), Operation::Read(&mut [dt.seconds]),
-            Operation::Write(&[PCF8523_MINUTES]), Operation::Read(&mut [dt.minutes]),
-            Operation::Write(&[PCF8523_HOURS]), Operation::Read(&mut [dt.hours]),
-            Operation::Write(&[PCF8523_DAYS]), Operation::Read(&mut [dt.day]),
-            Operation::Write(&[PCF8523_WEEKDAYS]), Operation::Read(&mut [dt.day_of_week]),
-            Operation::Write(&[PCF8523_MONTHS]), Operation::Read(&mut [dt.month]),
-            Operation::Write(&[PCF8523_YEARS]), Operation::Read(&mut [dt.year]),
-        ]).map_err(Pcf8523Error::I2C)?;
-        Ok(dt.decode())
+    // read all time registers in a single burst so the chip freezes them for the whole access
+    pub async fn get_datetime(&mut self) -> Result<DateTime, Pcf8523Error<I2C::Error>> {
+        let mode_12hr = self.get_hour_mode_12hr().await?;
+        let mut regs = [0u8; 7];
+        self.read_regs(PCF8523_SECONDS, &mut regs).await?;
+        Ok(DateTime::decode(regs, mode_12hr))
+    }
+
+    // as get_datetime, failing with a DateTimeError when the registers hold an impossible date or a
+    // weekday that disagrees with the date in the given century, e.g. DEFAULT_CENTURY
+    pub async fn get_datetime_checked(&mut self, century: u16) -> Result<DateTime, Pcf8523Error<I2C::Error>> {
+        let dt = self.get_datetime().await?;
+        dt.validate_weekday(century).map_err(Pcf8523Error::DateTime)?;
+        Ok(dt)
     }
 
-    pub fn get_day_of_month(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
-        Ok(decode_bcd(self.read_reg(PCF8523_DAYS)? & 0b11_1111))
+    pub async fn get_day_of_month(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        Ok(decode_bcd(self.read_reg(PCF8523_DAYS).await? & 0b11_1111))
     }
 
-    pub fn get_day_of_week(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
-        Ok(decode_bcd(self.read_reg(PCF8523_WEEKDAYS)? & 0b111))
+    pub async fn get_day_of_week(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        Ok(decode_bcd(self.read_reg(PCF8523_WEEKDAYS).await? & 0b111))
     }
 
-    pub fn get_interrupt_enabled(&mut self, interrupt: Pcf8523Interrupt) -> Result<bool, Pcf8523Error<I2C::Error>> {
+    // true in 12-hour mode
+    pub async fn get_hour_mode_12hr(&mut self) -> Result<bool, Pcf8523Error<I2C::Error>> {
+        Ok(get_bits(self.read_reg(PCF8523_CONTROL_1).await?, 1, 3) == 1)
+    }
+
+    // in the format of the current hour mode, see Hours::to_24_hour
+    pub async fn get_hours(&mut self) -> Result<Hours, Pcf8523Error<I2C::Error>> {
+        let mode_12hr = self.get_hour_mode_12hr().await?;
+        Ok(Hours::decode(self.read_reg(PCF8523_HOURS).await?, mode_12hr))
+    }
+
+    pub async fn get_interrupt_enabled(&mut self, interrupt: Pcf8523Interrupt) -> Result<bool, Pcf8523Error<I2C::Error>> {
         Ok(
             match interrupt {
-                Pcf8523Interrupt::Alarm => get_bits(self.read_reg(PCF8523_CONTROL_1)?, 1, 1) == 1,
-                Pcf8523Interrupt::BatterySwitchOver => get_bits(self.read_reg(PCF8523_CONTROL_3)?, 1, 1) == 1,
-                Pcf8523Interrupt::CountdownTimerA => get_bits(self.read_reg(PCF8523_CONTROL_2)?, 1, 1) == 1,
-                Pcf8523Interrupt::CountdownTimerB => get_bits(self.read_reg(PCF8523_CONTROL_2)?, 1, 0) == 1,
-                Pcf8523Interrupt::Second =>  get_bits(self.read_reg(PCF8523_CONTROL_1)?, 1, 2) == 1,
-                Pcf8523Interrupt::WatchdogTimerA => get_bits(self.read_reg(PCF8523_CONTROL_2)?, 1, 2) == 1,
+                Pcf8523Interrupt::Alarm => get_bits(self.read_reg(PCF8523_CONTROL_1).await?, 1, 1) == 1,
+                Pcf8523Interrupt::BatteryLow => get_bits(self.read_reg(PCF8523_CONTROL_3).await?, 1, 0) == 1,
+                Pcf8523Interrupt::BatterySwitchOver => get_bits(self.read_reg(PCF8523_CONTROL_3).await?, 1, 1) == 1,
+                Pcf8523Interrupt::CountdownTimerA => get_bits(self.read_reg(PCF8523_CONTROL_2).await?, 1, 1) == 1,
+                Pcf8523Interrupt::CountdownTimerB => get_bits(self.read_reg(PCF8523_CONTROL_2).await?, 1, 0) == 1,
+                Pcf8523Interrupt::Second =>  get_bits(self.read_reg(PCF8523_CONTROL_1).await?, 1, 2) == 1,
+                Pcf8523Interrupt::WatchdogTimerA => get_bits(self.read_reg(PCF8523_CONTROL_2).await?, 1, 2) == 1,
             }
         )
     }
 
-    // fn get_hours(&mut self) -> Result<Hours, Pcf8523Error<I2C::Error>> {
-    //     let reg_val = self.read_reg(PCF8523_HOURS)?;
-    //     let hours = if get_bits(self.read_reg(PCF8523_CONTROL_1)?, 1, 0b1000) == 0 {
-    //         Hours::new(decode_bcd(get_bits(reg_val, 6, 0)), None)
-    //     } else {
-    //         Hours::new(decode_bcd(get_bits(reg_val, 5, 0)), get_bits(reg_val, 1, 5).try_into().unwrap())
-    //     };
-    //     Ok(hours)
-    // }
+    pub async fn get_minutes(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        Ok(decode_bcd(self.read_reg(PCF8523_MINUTES).await? & 0b111_1111))
+    }
+
+    pub async fn get_month(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        Ok(decode_bcd(self.read_reg(PCF8523_MONTHS).await? & 0b1_1111))
+    }
 
-    pub fn get_minutes(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
-        Ok(decode_bcd(self.read_reg(PCF8523_MINUTES)? & 0b111_1111))
+    pub async fn get_offset(&mut self) -> Result<Offset, Pcf8523Error<I2C::Error>> {
+        Ok(Offset::decode(self.read_reg(PCF8523_OFFSET).await?))
     }
 
-    pub fn get_month(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
-        Ok(decode_bcd(self.read_reg(PCF8523_MONTHS)? & 0b1_1111))
+    pub async fn get_power_management(&mut self) -> Result<PowerManagement, Pcf8523Error<I2C::Error>> {
+        PowerManagement::try_from(get_bits(self.read_reg(PCF8523_CONTROL_3).await?, 3, 5))
+            .map_err(|_| Pcf8523Error::InvalidRegisterValue(PCF8523_CONTROL_3))
     }
 
-    pub fn get_seconds(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
-        Ok(decode_bcd(self.read_reg(PCF8523_SECONDS)? & 0b111_1111))
+    pub async fn get_seconds(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        Ok(decode_bcd(self.read_reg(PCF8523_SECONDS).await? & 0b111_1111))
     }
 
-    pub fn get_year(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
-        Ok(decode_bcd(self.read_reg(PCF8523_YEARS)? & 0b1111_1111))
+    // intended for boot, LostSincePowerFailure stays until the time is set again with set_datetime
+    // or the flag is cleared with clear_oscillator_stop_flag
+    pub async fn get_time_status(&mut self) -> Result<TimeStatus, Pcf8523Error<I2C::Error>> {
+        Ok(
+            if self.get_clock_integrity_guaranteed().await? { TimeStatus::Valid }
+            else { TimeStatus::LostSincePowerFailure }
+        )
     }
 
-    pub fn read_reg(&mut self, reg: u8) -> Result<u8, Pcf8523Error<I2C::Error>> {
+    pub async fn get_timer_a_clock(&mut self) -> Result<TimerSourceClock, Pcf8523Error<I2C::Error>> {
+        Ok(TimerSourceClock::from(self.read_reg(PCF8523_TMR_A_FREQ_CTRL).await?))
+    }
+
+    pub async fn get_timer_a_mode(&mut self) -> Result<TimerAMode, Pcf8523Error<I2C::Error>> {
+        Ok(TimerAMode::from(get_bits(self.read_reg(PCF8523_TMR_CLKOUT_CTRL).await?, 2, 1)))
+    }
+
+    // remaining count of the running timer
+    pub async fn get_timer_a_value(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        self.read_reg(PCF8523_TMR_A_REG).await
+    }
+
+    pub async fn get_timer_b_clock(&mut self) -> Result<TimerSourceClock, Pcf8523Error<I2C::Error>> {
+        Ok(TimerSourceClock::from(get_bits(self.read_reg(PCF8523_TMR_B_FREQ_CTRL).await?, 3, 0)))
+    }
+
+    pub async fn get_timer_b_enabled(&mut self) -> Result<bool, Pcf8523Error<I2C::Error>> {
+        Ok(get_bits(self.read_reg(PCF8523_TMR_CLKOUT_CTRL).await?, 1, 0) == 1)
+    }
+
+    pub async fn get_timer_b_interrupt_mode(&mut self) -> Result<TimerInterruptMode, Pcf8523Error<I2C::Error>> {
+        Ok(TimerInterruptMode::from(get_bits(self.read_reg(PCF8523_TMR_CLKOUT_CTRL).await?, 1, 6)))
+    }
+
+    pub async fn get_timer_b_pulse_width(&mut self) -> Result<TimerBPulseWidth, Pcf8523Error<I2C::Error>> {
+        Ok(TimerBPulseWidth::from(get_bits(self.read_reg(PCF8523_TMR_B_FREQ_CTRL).await?, 3, 4)))
+    }
+
+    // remaining count of the running timer
+    pub async fn get_timer_b_value(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        self.read_reg(PCF8523_TMR_B_REG).await
+    }
+
+    pub async fn get_year(&mut self) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        Ok(decode_bcd(self.read_reg(PCF8523_YEARS).await?))
+    }
+
+    // drops the cached values so they are read again on next use, call after the chip lost power
+    pub fn invalidate_cache(&mut self) {
+        if let Some(cache) = &mut self.cache { cache.invalidate(); }
+    }
+
+    // reads CONTROL_1 to CONTROL_3 in one access, note that reading CONTROL_2 clears WTAF
+    pub async fn pending_interrupts(&mut self) -> Result<Pcf8523Interrupts, Pcf8523Error<I2C::Error>> {
+        Ok(Pcf8523Interrupts::from_flags(&self.read_control_regs().await?))
+    }
+
+    pub async fn read_control1(&mut self) -> Result<Control1, Pcf8523Error<I2C::Error>> {
+        Ok(Control1::from(self.read_reg(PCF8523_CONTROL_1).await?))
+    }
+
+    pub async fn read_control2(&mut self) -> Result<Control2, Pcf8523Error<I2C::Error>> {
+        Ok(Control2::from(self.read_reg(PCF8523_CONTROL_2).await?))
+    }
+
+    pub async fn read_control3(&mut self) -> Result<Control3, Pcf8523Error<I2C::Error>> {
+        Ok(Control3::from(self.read_reg(PCF8523_CONTROL_3).await?))
+    }
+
+    // all three control registers in a single read, note that reading CONTROL_2 clears WTAF
+    pub async fn read_controls(&mut self) -> Result<(Control1, Control2, Control3), Pcf8523Error<I2C::Error>> {
+        let [control_1, control_2, control_3] = self.read_control_regs().await?;
+        Ok((Control1::from(control_1), Control2::from(control_2), Control3::from(control_3)))
+    }
+
+    // CONTROL_1 to CONTROL_3
+    async fn read_control_regs(&mut self) -> Result<[u8; 3], Pcf8523Error<I2C::Error>> {
+        let mut controls = [0u8; 3];
+        self.read_regs(PCF8523_CONTROL_1, &mut controls).await?;
+        Ok(controls)
+    }
+
+    // served from the cache when enabled and the register is cached
+    pub async fn read_reg(&mut self, reg: u8) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        if let Some(val) = self.cache.and_then(|cache| cache.get(reg)) { return Ok(val); }
         let mut buffer = [0u8];
-        self.i2c.write_read(PCF8523_I2C_ADDRESS, &[reg], &mut buffer).map_err(Pcf8523Error::I2C)?;
+        self.i2c.write_read(PCF8523_I2C_ADDRESS, &[reg], &mut buffer).await.map_err(Pcf8523Error::I2C)?;
+        self.update_cache(reg, &buffer);
         Ok(buffer[0])
     }
 
-    pub fn select_hour_mode(&mut self, mode_12hr: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_1)?;
-        set_bits(&mut val, mode_12hr as u8, 3, 0b1000);
-        self.write_reg(PCF8523_CONTROL_1, val)?;
+    // reads consecutive registers starting at start in a single burst, the address auto-increments
+    pub async fn read_regs(&mut self, start: u8, buffer: &mut [u8]) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.i2c.write_read(PCF8523_I2C_ADDRESS, &[start], buffer).await.map_err(Pcf8523Error::I2C)?;
+        self.update_cache(start, buffer);
         Ok(())
     }
 
-    pub fn select_oscillator_capacitor(&mut self, cap12_5pf: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_1)?;
+    async fn restart_time_circuits(&mut self, mut control_1: u8) -> Result<(), Pcf8523Error<I2C::Error>> {
+        set_bits(&mut control_1, 0, 5, 0b10_0000);
+        self.write_reg(PCF8523_CONTROL_1, control_1).await
+    }
+
+    // sets the 12_24 bit and rewrites the hours and hour alarm registers in the new format
+    pub async fn select_hour_mode(&mut self, mode_12hr: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let control_1 = self.read_reg(PCF8523_CONTROL_1).await?;
+        let current_12hr = get_bits(control_1, 1, 3) == 1;
+        if current_12hr == mode_12hr { return Ok(()); }
+        let hours = Hours::decode(self.read_reg(PCF8523_HOURS).await?, current_12hr).to_24_hour();
+        self.write_hour_mode(control_1, mode_12hr, hours).await
+    }
+
+    pub async fn select_oscillator_capacitor(&mut self, cap12_5pf: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_1).await?;
         set_bits(&mut val, cap12_5pf as u8, 7, 0b1000_0000);
-        self.write_reg(PCF8523_CONTROL_1, val)?;
+        self.write_reg(PCF8523_CONTROL_1, val).await?;
         Ok(())
     }
 
-    pub fn select_power_management(&mut self, power_management: PowerManagement) -> Result<(), Pcf8523Error<I2C::Error>> {
-        let mut val = self.read_reg(PCF8523_CONTROL_3)?;
+    pub async fn select_power_management(&mut self, power_management: PowerManagement) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_3).await?;
         set_bits(&mut val, power_management as u8, 5, 0b1110_0000);
-        self.write_reg(PCF8523_CONTROL_3, val)?;
+        self.write_reg(PCF8523_CONTROL_3, val).await?;
+        Ok(())
+    }
+
+    // Disabled stops the timer, Countdown or Watchdog starts it
+    pub async fn select_timer_a_mode(&mut self, mode: TimerAMode) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_TMR_CLKOUT_CTRL).await?;
+        set_bits(&mut val, mode as u8, 1, 0b110);
+        self.write_reg(PCF8523_TMR_CLKOUT_CTRL, val).await?;
+        Ok(())
+    }
+
+    pub async fn select_timer_b_enabled(&mut self, enabled: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_TMR_CLKOUT_CTRL).await?;
+        set_bits(&mut val, enabled as u8, 0, 0b1);
+        self.write_reg(PCF8523_TMR_CLKOUT_CTRL, val).await?;
+        Ok(())
+    }
+
+    pub async fn select_timer_b_interrupt_mode(&mut self, mode: TimerInterruptMode) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_TMR_CLKOUT_CTRL).await?;
+        set_bits(&mut val, mode as u8, 6, 0b100_0000);
+        self.write_reg(PCF8523_TMR_CLKOUT_CTRL, val).await?;
+        Ok(())
+    }
+
+    // writes all four alarm registers in one write, clears AF so an earlier match does not assert INT1
+    // as soon as AIE is set, then sets AIE. Note that reading CONTROL_2 to clear AF clears WTAF.
+    pub async fn set_alarm(&mut self, alarm: Alarm) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut control_1 = self.read_reg(PCF8523_CONTROL_1).await?;
+        let [minute, hour, day, weekday] = alarm.encode(get_bits(control_1, 1, 3) == 1);
+        set_bits(&mut control_1, 1, 1, 0b10);
+        self.i2c.write(PCF8523_I2C_ADDRESS, &[PCF8523_MINUTE_ALARM, minute, hour, day, weekday])
+            .await.map_err(Pcf8523Error::I2C)?;
+        self.clear_alarm_interrupt().await?;
+        self.write_reg(PCF8523_CONTROL_1, control_1).await
+    }
+
+    // only COF is modified, TAM, TBM, TAC and TBC are preserved
+    pub async fn set_clkout(&mut self, frequency: ClockOutFrequency) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_TMR_CLKOUT_CTRL).await?;
+        set_bits(&mut val, frequency as u8, 3, 0b11_1000);
+        self.write_reg(PCF8523_TMR_CLKOUT_CTRL, val).await?;
+        Ok(())
+    }
+
+    pub async fn set_correction_interrupt_enabled(&mut self, enabled: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut val = self.read_reg(PCF8523_CONTROL_1).await?;
+        set_bits(&mut val, enabled as u8, 0, 0b1);
+        self.write_reg(PCF8523_CONTROL_1, val).await?;
         Ok(())
     }
 
     // 8.6.8
-    // use an i2c transaction to complete accesses in < 1 second and avoid corruption
-    pub fn set_datetime(&mut self, dt: DateTime) -> Result<(), Pcf8523Error<I2C::Error>> {
-        dt.encode();
-        self.i2c.transaction(PCF8523_I2C_ADDRESS, &mut [
-            Operation::Write(&[PCF8523_SECONDS, dt.seconds]),
-            Operation::Write(&[PCF8523_MINUTES, dt.minutes]),
-            Operation::Write(&[PCF8523_HOURS, dt.hours]),
-            Operation::Write(&[PCF8523_DAYS, dt.day]),
-            Operation::Write(&[PCF8523_WEEKDAYS, dt.day_of_week]),
-            Operation::Write(&[PCF8523_MONTHS, dt.month]),
-            Operation::Write(&[PCF8523_YEARS, dt.year]),
-        ]).map_err(Pcf8523Error::I2C)?;
+    // write all time registers in a single burst so the prescaler is not able to increment them midway,
+    // this also clears the OS flag
+    pub async fn set_datetime(&mut self, dt: DateTime) -> Result<(), Pcf8523Error<I2C::Error>> {
+        dt.validate().map_err(Pcf8523Error::DateTime)?;
+        let regs = dt.encode(self.get_hour_mode_12hr().await?);
+        self.write_regs(PCF8523_SECONDS, &regs).await
+    }
+
+    // sets STOP and writes the time, then clears STOP as soon as release returns, which resets the
+    // prescaler so the first seconds increment follows 0.507813 s to 0.507935 s later
+    pub async fn set_datetime_synchronized<F: FnOnce()>(&mut self, dt: DateTime, release: F) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let control_1 = self.stop_and_set_datetime(dt).await?;
+        release().await;
+        self.restart_time_circuits(control_1).await
+    }
+
+    // as set_datetime_synchronized, releasing STOP SYNCHRONIZED_RELEASE_DELAY_US after the next rising
+    // edge of pin, e.g. a GPS PPS output, dt is the time at that edge
+    pub async fn set_datetime_synchronized_to_pin<PIN: InputPin, D: DelayNs>(&mut self, dt: DateTime, pin: &mut PIN, delay: &mut D) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let control_1 = self.stop_and_set_datetime(dt).await?;
+        let edge = Self::wait_for_rising_edge(pin).await;
+        if edge.is_ok() { delay.delay_us(SYNCHRONIZED_RELEASE_DELAY_US).await; }
+        // restart even if the pin failed so the clock is not left stopped
+        self.restart_time_circuits(control_1).await?;
+        edge
+    }
+
+    // read-modify-write of the register holding the enable bit, note that when that is CONTROL_2 the
+    // read clears WTAF. BLF can't be cleared and holds INT1 low while the battery is low, so disable
+    // BatteryLow once it has been reported and enable it again after the battery is replaced.
+    pub async fn set_interrupt_enabled(&mut self, interrupt: Pcf8523Interrupt, enabled: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let (reg, mask) = interrupt.enable_bit();
+        let mut val = self.read_reg(reg).await? | Pcf8523Interrupts::clearable_flags(reg);
+        set_bits(&mut val, enabled as u8, mask.trailing_zeros() as u8, mask);
+        self.write_reg(reg, val).await?;
+        Ok(())
+    }
+
+    // reads the control registers in one access and updates every enable bit in the set in one write,
+    // flags are written as 1 so none raised in between is cleared, note that reading CONTROL_2 clears WTAF
+    pub async fn set_interrupts_enabled(&mut self, interrupts: Pcf8523Interrupts, enabled: bool) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut controls = self.read_control_regs().await?;
+        for reg in [PCF8523_CONTROL_2, PCF8523_CONTROL_3] {
+            controls[reg as usize] |= Pcf8523Interrupts::clearable_flags(reg);
+        }
+        interrupts.set_enabled(&mut controls, enabled);
+        self.i2c.write(PCF8523_I2C_ADDRESS, &[PCF8523_CONTROL_1, controls[0], controls[1], controls[2]]).await.map_err(Pcf8523Error::I2C)?;
+        self.update_cache(PCF8523_CONTROL_1, &controls);
+        Ok(())
+    }
+
+    pub async fn set_offset(&mut self, offset: Offset) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.write_reg(PCF8523_OFFSET, offset.encode()).await
+    }
+
+    // writes the offset and sets CIE so each correction pulses INT1, no pulses occur while the value is 0
+    // use OffsetMode::EveryMinute to observe the corrections during calibration, a pulse per minute
+    // instead of every two hours, then clear CIE with set_correction_interrupt_enabled
+    pub async fn set_offset_with_correction_pulses(&mut self, offset: Offset) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.set_offset(offset).await?;
+        self.set_correction_interrupt_enabled(true).await
+    }
+
+    // writes Tmr_A_freq_ctrl and Tmr_A_reg in a single write
+    pub async fn set_timer_a(&mut self, clock: TimerSourceClock, value: u8) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.i2c.write(PCF8523_I2C_ADDRESS, &[PCF8523_TMR_A_FREQ_CTRL, clock as u8, value]).await.map_err(Pcf8523Error::I2C)?;
+        self.update_cache(PCF8523_TMR_A_FREQ_CTRL, &[clock as u8]);
+        Ok(())
+    }
+
+    // writes Tmr_B_freq_ctrl and Tmr_B_reg in a single write
+    pub async fn set_timer_b(&mut self, clock: TimerSourceClock, pulse_width: TimerBPulseWidth, value: u8) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut freq_ctrl = 0u8;
+        set_bits(&mut freq_ctrl, pulse_width as u8, 4, 0b111_0000);
+        set_bits(&mut freq_ctrl, clock as u8, 0, 0b111);
+        self.i2c.write(PCF8523_I2C_ADDRESS, &[PCF8523_TMR_B_FREQ_CTRL, freq_ctrl, value]).await.map_err(Pcf8523Error::I2C)?;
+        self.update_cache(PCF8523_TMR_B_FREQ_CTRL, &[freq_ctrl]);
+        Ok(())
+    }
+
+    pub async fn software_reset(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.write_reg(PCF8523_CONTROL_1, SOFTWARE_RESET).await
+    }
+
+    // sets STOP and writes the time, returns CONTROL_1 with STOP set so it can be cleared without another read
+    async fn stop_and_set_datetime(&mut self, dt: DateTime) -> Result<u8, Pcf8523Error<I2C::Error>> {
+        dt.validate().map_err(Pcf8523Error::DateTime)?;
+        let mut control_1 = self.read_reg(PCF8523_CONTROL_1).await?;
+        set_bits(&mut control_1, 1, 5, 0b10_0000);
+        self.write_reg(PCF8523_CONTROL_1, control_1).await?;
+        let written = self.write_regs(PCF8523_SECONDS, &dt.encode(get_bits(control_1, 1, 3) == 1)).await;
+        if written.is_err() {
+            // restart so the clock is not left stopped, the write error is the one returned
+            let _ = self.restart_time_circuits(control_1).await;
+        }
+        written?;
+        Ok(control_1)
+    }
+
+    // reads the control registers in one access and clears every flag that was set in one write
+    pub(crate) async fn take_interrupt_flags(&mut self) -> Result<Pcf8523Interrupts, Pcf8523Error<I2C::Error>> {
+        let mut controls = self.read_control_regs().await?;
+        let fired = Pcf8523Interrupts::from_flags(&controls);
+        if !fired.is_empty() {
+            fired.clear_flags(&mut controls);
+            self.i2c.write(PCF8523_I2C_ADDRESS, &[PCF8523_CONTROL_2, controls[1], controls[2]]).await.map_err(Pcf8523Error::I2C)?;
+        }
+        Ok(fired)
+    }
+
+    fn update_cache(&mut self, start: u8, values: &[u8]) {
+        if let Some(cache) = &mut self.cache { cache.update(start, values); }
+    }
+
+    // waits for the pin to go low first so an already high pin is not mistaken for an edge
+    #[maybe_async_cfg::only_if(sync)]
+    fn wait_for_rising_edge<PIN: InputPin>(pin: &mut PIN) -> Result<(), Pcf8523Error<I2C::Error>> {
+        while pin.is_high().map_err(|e| Pcf8523Error::Pin(e.kind()))? {}
+        while pin.is_low().map_err(|e| Pcf8523Error::Pin(e.kind()))? {}
         Ok(())
     }
 
-    pub fn software_reset(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
-        self.write_reg(PCF8523_CONTROL_1, 0b101_1000)
+    #[maybe_async_cfg::only_if(async)]
+    async fn wait_for_rising_edge<PIN: InputPin>(pin: &mut PIN) -> Result<(), Pcf8523Error<I2C::Error>> {
+        pin.wait_for_rising_edge().await.map_err(|e| Pcf8523Error::Pin(e.kind()))
     }
 
-    pub fn write_reg(&mut self, reg: u8, val: u8) -> Result<(), Pcf8523Error<I2C::Error>> {
-        Ok(self.i2c.write(PCF8523_I2C_ADDRESS, &[reg, val]).map_err(Pcf8523Error::I2C)?)
+    pub async fn write_control1(&mut self, control: Control1) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.write_reg(PCF8523_CONTROL_1, control.bits()).await
+    }
+
+    pub async fn write_control2(&mut self, control: Control2) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.write_reg(PCF8523_CONTROL_2, control.bits()).await
+    }
+
+    pub async fn write_control3(&mut self, control: Control3) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.write_reg(PCF8523_CONTROL_3, control.bits()).await
+    }
+
+    // STOP is held while HOURS and Hour_alarm are written so the hour can't roll over in between, which
+    // loses the fraction of the current second. A disabled hour alarm is left as is.
+    pub(crate) async fn write_hour_mode(&mut self, mut control_1: u8, mode_12hr: bool, hours: u8) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let mut hour_alarm = self.read_reg(PCF8523_HOUR_ALARM).await?;
+        if get_bits(hour_alarm, 1, 7) == 0 {
+            let alarm = Hours::decode(hour_alarm, get_bits(control_1, 1, 3) == 1).to_24_hour();
+            hour_alarm = Hours::from_24_hour(alarm, mode_12hr).encode();
+        }
+        set_bits(&mut control_1, mode_12hr as u8, 3, 0b1000);
+        let mut stopped = control_1;
+        set_bits(&mut stopped, 1, 5, 0b10_0000);
+        self.write_reg(PCF8523_CONTROL_1, stopped).await?;
+        let written = match self.write_reg(PCF8523_HOURS, Hours::from_24_hour(hours, mode_12hr).encode()).await {
+            Ok(()) => self.write_reg(PCF8523_HOUR_ALARM, hour_alarm).await,
+            err => err,
+        };
+        // STOP is put back even when a write failed, the write error is the one returned
+        let restored = self.write_reg(PCF8523_CONTROL_1, control_1).await;
+        written.and(restored)
+    }
+
+    pub async fn write_reg(&mut self, reg: u8, val: u8) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.i2c.write(PCF8523_I2C_ADDRESS, &[reg, val]).await.map_err(Pcf8523Error::I2C)?;
+        if reg == PCF8523_CONTROL_1 && val == SOFTWARE_RESET {
+            self.invalidate_cache();
+        } else {
+            self.update_cache(reg, &[val]);
+        }
+        Ok(())
+    }
+
+    // writes consecutive registers starting at start in a single burst, the address auto-increments
+    pub async fn write_regs(&mut self, start: u8, values: &[u8]) -> Result<(), Pcf8523Error<I2C::Error>> {
+        // adjacent writes in a transaction go out without a repeated start
+        self.i2c.transaction(PCF8523_I2C_ADDRESS, &mut [
+            Operation::Write(&[start]),
+            Operation::Write(values),
+        ]).await.map_err(Pcf8523Error::I2C)?;
+        self.update_cache(start, values);
+        Ok(())
     }
 }
\ No newline at end of file
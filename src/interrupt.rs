@@ -0,0 +1,67 @@
+use embedded_hal::digital::{Error, InputPin};
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::digital::Wait;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+use crate::driver::Pcf8523;
+#[cfg(feature = "async")]
+use crate::driver::Pcf8523Async;
+use crate::typedefs::{Pcf8523Error, Pcf8523Interrupts};
+
+// pairs the driver with the GPIO wired to INT1, which is open-drain and active low
+#[maybe_async_cfg::maybe(
+    idents(Pcf8523(sync = "Pcf8523", async = "Pcf8523Async"), Pcf8523Int(sync = "Pcf8523Int", async = "Pcf8523IntAsync")),
+    sync(keep_self),
+    async(feature = "async"),
+)]
+pub struct Pcf8523Int<I2C, PIN> {
+    rtc: Pcf8523<I2C>,
+    pin: PIN,
+}
+
+#[maybe_async_cfg::maybe(
+    idents(
+        Pcf8523(sync = "Pcf8523", async = "Pcf8523Async"),
+        Pcf8523Int(sync = "Pcf8523Int", async = "Pcf8523IntAsync"),
+        I2c(sync = "I2c", async = "AsyncI2c"),
+        InputPin(sync = "InputPin", async = "Wait"),
+    ),
+    sync(keep_self),
+    async(feature = "async"),
+)]
+impl<I2C: I2c, PIN: InputPin> Pcf8523Int<I2C, PIN> {
+    pub fn new(rtc: Pcf8523<I2C>, pin: PIN) -> Self {
+        Self { rtc, pin }
+    }
+
+    pub fn release(self) -> (Pcf8523<I2C>, PIN) {
+        (self.rtc, self.pin)
+    }
+
+    pub fn rtc(&mut self) -> &mut Pcf8523<I2C> {
+        &mut self.rtc
+    }
+
+    // waits for INT1 to assert then returns and clears the interrupts that fired. BatteryLow is a level
+    // and returned by every call while the battery is low, the caller must disable it after the first.
+    pub async fn wait_for_event(&mut self) -> Result<Pcf8523Interrupts, Pcf8523Error<I2C::Error>> {
+        loop {
+            self.wait_for_low().await?;
+            let fired = self.rtc.take_interrupt_flags().await?;
+            // correction pulses assert the line without setting a flag
+            if !fired.is_empty() { return Ok(fired); }
+        }
+    }
+
+    #[maybe_async_cfg::only_if(sync)]
+    fn wait_for_low(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        while !self.pin.is_low().map_err(|e| Pcf8523Error::Pin(e.kind()))? {}
+        Ok(())
+    }
+
+    #[maybe_async_cfg::only_if(async)]
+    async fn wait_for_low(&mut self) -> Result<(), Pcf8523Error<I2C::Error>> {
+        self.pin.wait_for_low().await.map_err(|e| Pcf8523Error::Pin(e.kind()))
+    }
+}
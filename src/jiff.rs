@@ -0,0 +1,92 @@
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+use jiff::civil::{self, Date, Time};
+use crate::datetime::{full_year, year_in_century, DateTime, DateTimeError, DEFAULT_CENTURY};
+use crate::driver::Pcf8523;
+#[cfg(feature = "async")]
+use crate::driver::Pcf8523Async;
+use crate::typedefs::Pcf8523Error;
+
+impl DateTime {
+    pub fn from_civil_datetime(datetime: &civil::DateTime, century: u16) -> Result<Self, DateTimeError> {
+        let year = year_in_century(datetime.year() as i32, century)?;
+        Ok(Self {
+            seconds: datetime.second() as u8,
+            minutes: datetime.minute() as u8,
+            hours: datetime.hour() as u8,
+            day: datetime.day() as u8,
+            day_of_week: datetime.weekday().to_sunday_zero_offset() as u8,
+            month: datetime.month() as u8,
+            year,
+        })
+    }
+
+    pub fn to_civil_datetime(&self, century: u16) -> Result<civil::DateTime, DateTimeError> {
+        let year = full_year(self.year, century)?;
+        if !(1..=12).contains(&self.month) { return Err(DateTimeError::InvalidMonth); }
+        if year > Date::MAX.year() as i32 { return Err(DateTimeError::InvalidYear); }
+        let date = Date::new(year as i16, self.month as i8, self.day as i8)
+            .map_err(|_| DateTimeError::InvalidDay)?;
+        if self.hours > 23 { return Err(DateTimeError::InvalidHours); }
+        if self.minutes > 59 { return Err(DateTimeError::InvalidMinutes); }
+        let time = Time::new(self.hours as i8, self.minutes as i8, self.seconds as i8, 0)
+            .map_err(|_| DateTimeError::InvalidSeconds)?;
+        Ok(civil::DateTime::from_parts(date, time))
+    }
+}
+
+impl TryFrom<civil::DateTime> for DateTime {
+    type Error = DateTimeError;
+    fn try_from(value: civil::DateTime) -> Result<Self, Self::Error> {
+        DateTime::from_civil_datetime(&value, DEFAULT_CENTURY)
+    }
+}
+
+impl TryFrom<DateTime> for civil::DateTime {
+    type Error = DateTimeError;
+    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
+        value.to_civil_datetime(DEFAULT_CENTURY)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(Pcf8523(sync = "Pcf8523", async = "Pcf8523Async"), I2c(sync = "I2c", async = "AsyncI2c")),
+    sync(keep_self),
+    async(feature = "async"),
+)]
+impl<I2C: I2c> Pcf8523<I2C> {
+    pub async fn get_civil_datetime(&mut self) -> Result<civil::DateTime, Pcf8523Error<I2C::Error>> {
+        self.get_datetime().await?.to_civil_datetime(DEFAULT_CENTURY).map_err(Pcf8523Error::DateTime)
+    }
+
+    pub async fn set_civil_datetime(&mut self, datetime: &civil::DateTime) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let dt = DateTime::from_civil_datetime(datetime, DEFAULT_CENTURY).map_err(Pcf8523Error::DateTime)?;
+        self.set_datetime(dt).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::{DayOfWeek, Month};
+
+    #[test]
+    fn to_civil_datetime_century_overflow_err() {
+        assert_eq!(DateTime::default().to_civil_datetime(40_000).unwrap_err(), DateTimeError::InvalidYear);
+    }
+
+    #[test]
+    fn to_civil_datetime_invalid_day_err() {
+        let dt = DateTime { day: 30, month: 2, year: 25, ..DateTime::default() };
+        assert_eq!(civil::DateTime::try_from(dt).unwrap_err(), DateTimeError::InvalidDay);
+    }
+
+    #[test]
+    fn to_civil_datetime_round_trip() {
+        let dt = DateTime::new(1, 2, 3, 29, DayOfWeek::Thursday, Month::February, 24).unwrap();
+        let cdt = dt.to_civil_datetime(2000).unwrap();
+        assert_eq!(cdt, civil::datetime(2024, 2, 29, 3, 2, 1, 0));
+        assert_eq!(DateTime::try_from(cdt).unwrap(), dt);
+    }
+}
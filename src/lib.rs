@@ -2,6 +2,21 @@
 
 pub mod registers;
 pub(crate) mod bits;
+pub(crate) mod cache;
 pub mod driver;
 pub mod typedefs;
-pub mod datetime;
\ No newline at end of file
+pub mod datetime;
+pub mod alarm;
+pub mod timer;
+pub mod offset;
+pub mod calibration;
+pub mod control;
+pub mod interrupt;
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "jiff")]
+mod jiff;
+#[cfg(feature = "rtcc")]
+mod rtcc;
+#[cfg(feature = "time")]
+mod time;
\ No newline at end of file
@@ -0,0 +1,125 @@
+use crate::bits::{get_bits, set_bits};
+
+// correction applied by one offset step, in ppm
+const MODE_0_STEP_PPM: f32 = 4.340;
+const MODE_1_STEP_PPM: f32 = 4.069;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffsetMode {
+    // correction pulses once every two hours
+    EveryTwoHours = 0x0,
+    // correction pulses once every minute
+    EveryMinute = 0x1,
+}
+impl OffsetMode {
+    pub fn step_ppm(&self) -> f32 {
+        match self {
+            OffsetMode::EveryTwoHours => MODE_0_STEP_PPM,
+            OffsetMode::EveryMinute => MODE_1_STEP_PPM,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Offset {
+    mode: OffsetMode,
+    value: i8,
+}
+
+impl Offset {
+    pub const MIN: i8 = -64;
+    pub const MAX: i8 = 63;
+
+    // value is a signed 7-bit step count, positive values speed the clock up
+    pub fn new(mode: OffsetMode, value: i8) -> Option<Self> {
+        if !(Self::MIN..=Self::MAX).contains(&value) { return None; }
+        Some(Self { mode, value })
+    }
+
+    // drift_ppm is positive when the RTC runs fast, returns None if the correction needs more than 7 bits
+    pub fn from_drift_ppm(mode: OffsetMode, drift_ppm: f32) -> Option<Self> {
+        let steps = round(-drift_ppm / mode.step_ppm());
+        if steps < Self::MIN as i32 || steps > Self::MAX as i32 { return None; }
+        Self::new(mode, steps as i8)
+    }
+
+    pub(crate) fn clamped(mode: OffsetMode, steps: i32) -> Self {
+        Self { mode, value: steps.clamp(Self::MIN as i32, Self::MAX as i32) as i8 }
+    }
+
+    pub fn mode(&self) -> OffsetMode {
+        self.mode
+    }
+
+    pub fn value(&self) -> i8 {
+        self.value
+    }
+
+    // correction applied by this setting, in ppm
+    pub fn ppm(&self) -> f32 {
+        self.value as f32 * self.mode.step_ppm()
+    }
+
+    pub(crate) fn encode(&self) -> u8 {
+        let mut reg = 0u8;
+        set_bits(&mut reg, self.mode as u8, 7, 0b1000_0000);
+        set_bits(&mut reg, self.value as u8, 0, 0b111_1111);
+        reg
+    }
+
+    pub(crate) fn decode(reg: u8) -> Self {
+        let mode = if get_bits(reg, 1, 7) == 0 { OffsetMode::EveryTwoHours } else { OffsetMode::EveryMinute };
+        // sign-extend the 7-bit two's complement value
+        let value = ((reg << 1) as i8) >> 1;
+        Self { mode, value }
+    }
+}
+
+// core has no f32::round
+pub(crate) fn round(a: f32) -> i32 {
+    if a >= 0.0 { (a + 0.5) as i32 } else { (a - 0.5) as i32 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_out_of_range_none() {
+        assert!(Offset::new(OffsetMode::EveryTwoHours, 64).is_none());
+        assert!(Offset::new(OffsetMode::EveryTwoHours, -65).is_none());
+    }
+
+    #[test]
+    fn encode_negative() {
+        let offset = Offset::new(OffsetMode::EveryMinute, -1).unwrap();
+        assert_eq!(offset.encode(), 0b1111_1111);
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        for value in Offset::MIN..=Offset::MAX {
+            let offset = Offset::new(OffsetMode::EveryTwoHours, value).unwrap();
+            assert_eq!(Offset::decode(offset.encode()), offset);
+        }
+    }
+
+    #[test]
+    fn from_drift_ppm_fast_clock_negative() {
+        // +10 ppm / 4.34 ppm = 2.3 steps
+        let offset = Offset::from_drift_ppm(OffsetMode::EveryTwoHours, 10.0).unwrap();
+        assert_eq!(offset.value(), -2);
+    }
+
+    #[test]
+    fn from_drift_ppm_slow_clock_positive() {
+        // -10 ppm / 4.069 ppm = -2.46 steps
+        let offset = Offset::from_drift_ppm(OffsetMode::EveryMinute, -10.0).unwrap();
+        assert_eq!(offset.value(), 2);
+    }
+
+    #[test]
+    fn from_drift_ppm_out_of_range_none() {
+        assert!(Offset::from_drift_ppm(OffsetMode::EveryTwoHours, 300.0).is_none());
+    }
+}
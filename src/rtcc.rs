@@ -0,0 +1,117 @@
+use embedded_hal::i2c::I2c;
+use rtcc::{DateTimeAccess, Hours, NaiveDate, NaiveDateTime, NaiveTime, Rtcc};
+use crate::bits::encode_bcd;
+use crate::datetime::{DateTimeError, DEFAULT_CENTURY};
+use crate::driver::Pcf8523;
+use crate::registers::{PCF8523_CONTROL_1, PCF8523_DAYS, PCF8523_MINUTES, PCF8523_MONTHS, PCF8523_SECONDS, PCF8523_WEEKDAYS, PCF8523_YEARS};
+use crate::typedefs::{Meridiem, Pcf8523Error};
+
+impl<I2C: I2c> DateTimeAccess for Pcf8523<I2C> {
+    type Error = Pcf8523Error<I2C::Error>;
+
+    fn datetime(&mut self) -> Result<NaiveDateTime, Self::Error> {
+        self.get_naive_datetime()
+    }
+
+    fn set_datetime(&mut self, datetime: &NaiveDateTime) -> Result<(), Self::Error> {
+        self.set_naive_datetime(datetime)
+    }
+}
+
+// weekdays are numbered 1 (Sunday) to 7 (Saturday)
+impl<I2C: I2c> Rtcc for Pcf8523<I2C> {
+    fn seconds(&mut self) -> Result<u8, Self::Error> {
+        self.get_seconds()
+    }
+
+    fn minutes(&mut self) -> Result<u8, Self::Error> {
+        self.get_minutes()
+    }
+
+    fn hours(&mut self) -> Result<Hours, Self::Error> {
+        let hours = self.get_hours()?;
+        Ok(match hours.meridiem() {
+            None => Hours::H24(hours.hours()),
+            Some(Meridiem::AM) => Hours::AM(hours.hours()),
+            Some(Meridiem::PM) => Hours::PM(hours.hours()),
+        })
+    }
+
+    fn time(&mut self) -> Result<NaiveTime, Self::Error> {
+        Ok(self.datetime()?.time())
+    }
+
+    fn weekday(&mut self) -> Result<u8, Self::Error> {
+        Ok(self.get_day_of_week()? + 1)
+    }
+
+    fn day(&mut self) -> Result<u8, Self::Error> {
+        self.get_day_of_month()
+    }
+
+    fn month(&mut self) -> Result<u8, Self::Error> {
+        self.get_month()
+    }
+
+    fn year(&mut self) -> Result<u16, Self::Error> {
+        Ok(DEFAULT_CENTURY + self.get_year()? as u16)
+    }
+
+    fn date(&mut self) -> Result<NaiveDate, Self::Error> {
+        Ok(self.datetime()?.date())
+    }
+
+    fn set_seconds(&mut self, seconds: u8) -> Result<(), Self::Error> {
+        if seconds > 59 { return Err(Pcf8523Error::DateTime(DateTimeError::InvalidSeconds)); }
+        self.write_reg(PCF8523_SECONDS, encode_bcd(seconds))
+    }
+
+    fn set_minutes(&mut self, minutes: u8) -> Result<(), Self::Error> {
+        if minutes > 59 { return Err(Pcf8523Error::DateTime(DateTimeError::InvalidMinutes)); }
+        self.write_reg(PCF8523_MINUTES, encode_bcd(minutes))
+    }
+
+    // selects 12 or 24 hour mode to match the parameter
+    fn set_hours(&mut self, hours: Hours) -> Result<(), Self::Error> {
+        let (mode_12hr, hours) = match hours {
+            Hours::H24(h) if h < 24 => (false, h),
+            Hours::AM(h) if (1..=12).contains(&h) => (true, h % 12),
+            Hours::PM(h) if (1..=12).contains(&h) => (true, h % 12 + 12),
+            _ => return Err(Pcf8523Error::DateTime(DateTimeError::InvalidHours)),
+        };
+        let control_1 = self.read_reg(PCF8523_CONTROL_1)?;
+        self.write_hour_mode(control_1, mode_12hr, hours)
+    }
+
+    fn set_time(&mut self, time: &NaiveTime) -> Result<(), Self::Error> {
+        let mut dt = self.get_datetime()?;
+        dt.set_naive_time(time);
+        Pcf8523::set_datetime(self, dt)
+    }
+
+    fn set_weekday(&mut self, weekday: u8) -> Result<(), Self::Error> {
+        if !(1..=7).contains(&weekday) { return Err(Pcf8523Error::DateTime(DateTimeError::InvalidWeekday)); }
+        self.write_reg(PCF8523_WEEKDAYS, weekday - 1)
+    }
+
+    fn set_day(&mut self, day: u8) -> Result<(), Self::Error> {
+        if !(1..=31).contains(&day) { return Err(Pcf8523Error::DateTime(DateTimeError::InvalidDay)); }
+        self.write_reg(PCF8523_DAYS, encode_bcd(day))
+    }
+
+    fn set_month(&mut self, month: u8) -> Result<(), Self::Error> {
+        if !(1..=12).contains(&month) { return Err(Pcf8523Error::DateTime(DateTimeError::InvalidMonth)); }
+        self.write_reg(PCF8523_MONTHS, encode_bcd(month))
+    }
+
+    fn set_year(&mut self, year: u16) -> Result<(), Self::Error> {
+        if !(DEFAULT_CENTURY..DEFAULT_CENTURY + 100).contains(&year) { return Err(Pcf8523Error::DateTime(DateTimeError::InvalidYear)); }
+        self.write_reg(PCF8523_YEARS, encode_bcd((year - DEFAULT_CENTURY) as u8))
+    }
+
+    fn set_date(&mut self, date: &NaiveDate) -> Result<(), Self::Error> {
+        let mut dt = self.get_datetime()?;
+        dt.set_naive_date(date, DEFAULT_CENTURY).map_err(Pcf8523Error::DateTime)?;
+        Pcf8523::set_datetime(self, dt)
+    }
+}
@@ -0,0 +1,92 @@
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+use time::{Date, Month, PrimitiveDateTime, Time};
+use crate::datetime::{full_year, year_in_century, DateTime, DateTimeError, DEFAULT_CENTURY};
+use crate::driver::Pcf8523;
+#[cfg(feature = "async")]
+use crate::driver::Pcf8523Async;
+use crate::typedefs::Pcf8523Error;
+
+impl DateTime {
+    pub fn from_primitive_datetime(datetime: &PrimitiveDateTime, century: u16) -> Result<Self, DateTimeError> {
+        let year = year_in_century(datetime.year(), century)?;
+        Ok(Self {
+            seconds: datetime.second(),
+            minutes: datetime.minute(),
+            hours: datetime.hour(),
+            day: datetime.day(),
+            day_of_week: datetime.weekday().number_days_from_sunday(),
+            month: datetime.month() as u8,
+            year,
+        })
+    }
+
+    pub fn to_primitive_datetime(&self, century: u16) -> Result<PrimitiveDateTime, DateTimeError> {
+        let year = full_year(self.year, century)?;
+        let month = Month::try_from(self.month).map_err(|_| DateTimeError::InvalidMonth)?;
+        if year > Date::MAX.year() { return Err(DateTimeError::InvalidYear); }
+        let date = Date::from_calendar_date(year, month, self.day)
+            .map_err(|_| DateTimeError::InvalidDay)?;
+        if self.hours > 23 { return Err(DateTimeError::InvalidHours); }
+        if self.minutes > 59 { return Err(DateTimeError::InvalidMinutes); }
+        let time = Time::from_hms(self.hours, self.minutes, self.seconds).map_err(|_| DateTimeError::InvalidSeconds)?;
+        Ok(PrimitiveDateTime::new(date, time))
+    }
+}
+
+impl TryFrom<PrimitiveDateTime> for DateTime {
+    type Error = DateTimeError;
+    fn try_from(value: PrimitiveDateTime) -> Result<Self, Self::Error> {
+        DateTime::from_primitive_datetime(&value, DEFAULT_CENTURY)
+    }
+}
+
+impl TryFrom<DateTime> for PrimitiveDateTime {
+    type Error = DateTimeError;
+    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
+        value.to_primitive_datetime(DEFAULT_CENTURY)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    idents(Pcf8523(sync = "Pcf8523", async = "Pcf8523Async"), I2c(sync = "I2c", async = "AsyncI2c")),
+    sync(keep_self),
+    async(feature = "async"),
+)]
+impl<I2C: I2c> Pcf8523<I2C> {
+    pub async fn get_primitive_datetime(&mut self) -> Result<PrimitiveDateTime, Pcf8523Error<I2C::Error>> {
+        self.get_datetime().await?.to_primitive_datetime(DEFAULT_CENTURY).map_err(Pcf8523Error::DateTime)
+    }
+
+    pub async fn set_primitive_datetime(&mut self, datetime: &PrimitiveDateTime) -> Result<(), Pcf8523Error<I2C::Error>> {
+        let dt = DateTime::from_primitive_datetime(datetime, DEFAULT_CENTURY).map_err(Pcf8523Error::DateTime)?;
+        self.set_datetime(dt).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::{DayOfWeek, Month as DtMonth};
+
+    #[test]
+    fn to_primitive_datetime_century_overflow_err() {
+        assert_eq!(DateTime::default().to_primitive_datetime(u16::MAX).unwrap_err(), DateTimeError::InvalidYear);
+    }
+
+    #[test]
+    fn to_primitive_datetime_invalid_day_err() {
+        let dt = DateTime { day: 30, month: 2, year: 25, ..DateTime::default() };
+        assert_eq!(PrimitiveDateTime::try_from(dt).unwrap_err(), DateTimeError::InvalidDay);
+    }
+
+    #[test]
+    fn to_primitive_datetime_round_trip() {
+        let dt = DateTime::new(1, 2, 3, 29, DayOfWeek::Thursday, DtMonth::February, 24).unwrap();
+        let pdt = dt.to_primitive_datetime(2000).unwrap();
+        let expected = Date::from_calendar_date(2024, Month::February, 29).unwrap();
+        assert_eq!(pdt, PrimitiveDateTime::new(expected, Time::from_hms(3, 2, 1).unwrap()));
+        assert_eq!(DateTime::try_from(pdt).unwrap(), dt);
+    }
+}
@@ -0,0 +1,104 @@
+// TAQ/TBQ source clock for the countdown timers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimerSourceClock {
+    Hz4096 = 0x0,
+    Hz64 = 0x1,
+    Hz1 = 0x2,
+    PerMinute = 0x3,
+    PerHour = 0x4,
+}
+impl From<u8> for TimerSourceClock {
+    fn from(value: u8) -> Self {
+        match value & 0b111 {
+            0x0 => TimerSourceClock::Hz4096,
+            0x1 => TimerSourceClock::Hz64,
+            0x2 => TimerSourceClock::Hz1,
+            0x3 => TimerSourceClock::PerMinute,
+            // 0b1xx all select 1/3600 Hz
+            _ => TimerSourceClock::PerHour,
+        }
+    }
+}
+
+// TAC bits of Tmr_CLKOUT_ctrl
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimerAMode {
+    Disabled = 0x0,
+    Countdown = 0x1,
+    Watchdog = 0x2,
+}
+impl From<u8> for TimerAMode {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0x1 => TimerAMode::Countdown,
+            0x2 => TimerAMode::Watchdog,
+            // 0b11 also disables timer A
+            _ => TimerAMode::Disabled,
+        }
+    }
+}
+
+// TBW low pulse width of the timer B pulsed interrupt
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimerBPulseWidth {
+    Ms46_875 = 0x0,
+    Ms62_5 = 0x1,
+    Ms78_125 = 0x2,
+    Ms93_75 = 0x3,
+    Ms125 = 0x4,
+    Ms156_25 = 0x5,
+    Ms187_5 = 0x6,
+    Ms218_75 = 0x7,
+}
+impl From<u8> for TimerBPulseWidth {
+    fn from(value: u8) -> Self {
+        match value & 0b111 {
+            0x0 => TimerBPulseWidth::Ms46_875,
+            0x1 => TimerBPulseWidth::Ms62_5,
+            0x2 => TimerBPulseWidth::Ms78_125,
+            0x3 => TimerBPulseWidth::Ms93_75,
+            0x4 => TimerBPulseWidth::Ms125,
+            0x5 => TimerBPulseWidth::Ms156_25,
+            0x6 => TimerBPulseWidth::Ms187_5,
+            _ => TimerBPulseWidth::Ms218_75,
+        }
+    }
+}
+
+// TAM/TBM bits of Tmr_CLKOUT_ctrl
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimerInterruptMode {
+    Permanent = 0x0,
+    Pulsed = 0x1,
+}
+impl From<u8> for TimerInterruptMode {
+    fn from(value: u8) -> Self {
+        match value & 0b1 {
+            0x0 => TimerInterruptMode::Permanent,
+            _ => TimerInterruptMode::Pulsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timer_source_clock_per_hour_aliases() {
+        for value in 0x4..=0x7 {
+            assert_eq!(TimerSourceClock::from(value), TimerSourceClock::PerHour);
+        }
+    }
+
+    #[test]
+    fn timer_a_mode_disabled_aliases() {
+        assert_eq!(TimerAMode::from(0b00), TimerAMode::Disabled);
+        assert_eq!(TimerAMode::from(0b11), TimerAMode::Disabled);
+    }
+
+    #[test]
+    fn timer_b_pulse_width_ignores_upper_bits() {
+        assert_eq!(TimerBPulseWidth::from(0b1111_0101), TimerBPulseWidth::Ms156_25);
+    }
+}
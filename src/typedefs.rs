@@ -1,10 +1,75 @@
-#[derive(Debug, PartialEq)]
+use core::ops::BitOr;
+use embedded_hal::digital::ErrorKind;
+use crate::bits::{decode_bcd, encode_bcd, get_bits, set_bits};
+use crate::datetime::DateTimeError;
+use crate::registers::{PCF8523_CONTROL_1, PCF8523_CONTROL_2, PCF8523_CONTROL_3};
+
+// BLF, BSF and the battery low detection half of PM in CONTROL_3
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BatteryStatus {
+    battery_low: bool,
+    switch_over_occurred: bool,
+    low_detection_enabled: bool,
+}
+impl BatteryStatus {
+    // BLF is only meaningful while low detection is enabled
+    pub fn battery_low(&self) -> bool {
+        self.battery_low
+    }
+
+    pub fn switch_over_occurred(&self) -> bool {
+        self.switch_over_occurred
+    }
+
+    pub fn low_detection_enabled(&self) -> bool {
+        self.low_detection_enabled
+    }
+
+    pub(crate) fn decode(control_3: u8) -> Self {
+        Self {
+            battery_low: get_bits(control_3, 1, 2) == 1,
+            switch_over_occurred: get_bits(control_3, 1, 3) == 1,
+            // PM 1xx disables battery low detection
+            low_detection_enabled: get_bits(control_3, 1, 7) == 0,
+        }
+    }
+}
+
+// COF bits of Tmr_CLKOUT_ctrl
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClockOutFrequency {
+    Hz32768 = 0x0,
+    Hz16384 = 0x1,
+    Hz8192 = 0x2,
+    Hz4096 = 0x3,
+    Hz1024 = 0x4,
+    Hz32 = 0x5,
+    Hz1 = 0x6,
+    Disabled = 0x7,
+}
+impl From<u8> for ClockOutFrequency {
+    fn from(value: u8) -> Self {
+        match value & 0b111 {
+            0x0 => ClockOutFrequency::Hz32768,
+            0x1 => ClockOutFrequency::Hz16384,
+            0x2 => ClockOutFrequency::Hz8192,
+            0x3 => ClockOutFrequency::Hz4096,
+            0x4 => ClockOutFrequency::Hz1024,
+            0x5 => ClockOutFrequency::Hz32,
+            0x6 => ClockOutFrequency::Hz1,
+            _ => ClockOutFrequency::Disabled,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Meridiem {
     AM = 0x0,
     PM = 0x1,
 }
 
-#[derive(Debug, PartialEq)]
+// hours as held by the chip, meridiem is None in 24-hour mode
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Hours {
     hours: u8,
     meridiem: Option<Meridiem>
@@ -16,24 +81,190 @@ impl Hours {
         // }
         Self { hours, meridiem }
     }
+
+    pub fn hours(&self) -> u8 {
+        self.hours
+    }
+
+    pub fn meridiem(&self) -> Option<Meridiem> {
+        self.meridiem
+    }
+
+    // 0-23 whichever mode the value was read in, 12 AM is midnight
+    pub fn to_24_hour(&self) -> u8 {
+        match self.meridiem {
+            None => self.hours,
+            Some(Meridiem::AM) => self.hours % 12,
+            Some(Meridiem::PM) => self.hours % 12 + 12,
+        }
+    }
+
+    pub(crate) fn from_24_hour(hours: u8, mode_12hr: bool) -> Self {
+        if !mode_12hr { return Self::new(hours, None); }
+        let meridiem = if hours < 12 { Meridiem::AM } else { Meridiem::PM };
+        let hours = match hours % 12 { 0 => 12, h => h };
+        Self::new(hours, Some(meridiem))
+    }
+
+    // hours register layout depends on the 12_24 bit of CONTROL_1
+    pub(crate) fn decode(reg: u8, mode_12hr: bool) -> Self {
+        if !mode_12hr { return Self::new(decode_bcd(get_bits(reg, 6, 0)), None); }
+        let meridiem = if get_bits(reg, 1, 5) == 0 { Meridiem::AM } else { Meridiem::PM };
+        Self::new(decode_bcd(get_bits(reg, 5, 0)), Some(meridiem))
+    }
+
+    pub(crate) fn encode(&self) -> u8 {
+        let mut reg = encode_bcd(self.hours);
+        if let Some(meridiem) = self.meridiem { set_bits(&mut reg, meridiem as u8, 5, 0b10_0000); }
+        reg
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum Pcf8523Error<E> {
     I2C(E),
+    DateTime(DateTimeError),
+    // interrupt pin could not be read
+    Pin(ErrorKind),
+    // register address holding a value the driver cannot represent
+    InvalidRegisterValue(u8),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Pcf8523Interrupt {
     Alarm,
+    BatteryLow,
     BatterySwitchOver,
     CountdownTimerA,
     CountdownTimerB,
     Second,
     WatchdogTimerA,
 }
+impl Pcf8523Interrupt {
+    const ALL: [Pcf8523Interrupt; 7] = [
+        Pcf8523Interrupt::Alarm,
+        Pcf8523Interrupt::BatteryLow,
+        Pcf8523Interrupt::BatterySwitchOver,
+        Pcf8523Interrupt::CountdownTimerA,
+        Pcf8523Interrupt::CountdownTimerB,
+        Pcf8523Interrupt::Second,
+        Pcf8523Interrupt::WatchdogTimerA,
+    ];
 
-#[derive(Debug, PartialEq)]
+    // control register address and mask of the enable bit
+    pub(crate) fn enable_bit(&self) -> (u8, u8) {
+        match self {
+            Pcf8523Interrupt::Alarm => (PCF8523_CONTROL_1, 0b10),
+            Pcf8523Interrupt::BatteryLow => (PCF8523_CONTROL_3, 0b1),
+            Pcf8523Interrupt::BatterySwitchOver => (PCF8523_CONTROL_3, 0b10),
+            Pcf8523Interrupt::CountdownTimerA => (PCF8523_CONTROL_2, 0b10),
+            Pcf8523Interrupt::CountdownTimerB => (PCF8523_CONTROL_2, 0b1),
+            Pcf8523Interrupt::Second => (PCF8523_CONTROL_1, 0b100),
+            Pcf8523Interrupt::WatchdogTimerA => (PCF8523_CONTROL_2, 0b100),
+        }
+    }
+
+    // control register address and mask of the flag bit
+    fn flag_bit(&self) -> (u8, u8) {
+        match self {
+            Pcf8523Interrupt::Alarm => (PCF8523_CONTROL_2, 0b1000),
+            Pcf8523Interrupt::BatteryLow => (PCF8523_CONTROL_3, 0b100),
+            Pcf8523Interrupt::BatterySwitchOver => (PCF8523_CONTROL_3, 0b1000),
+            Pcf8523Interrupt::CountdownTimerA => (PCF8523_CONTROL_2, 0b100_0000),
+            Pcf8523Interrupt::CountdownTimerB => (PCF8523_CONTROL_2, 0b10_0000),
+            Pcf8523Interrupt::Second => (PCF8523_CONTROL_2, 0b1_0000),
+            Pcf8523Interrupt::WatchdogTimerA => (PCF8523_CONTROL_2, 0b1000_0000),
+        }
+    }
+}
+
+// set of interrupt sources
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pcf8523Interrupts(u8);
+impl Pcf8523Interrupts {
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    pub fn contains(&self, interrupt: Pcf8523Interrupt) -> bool {
+        self.0 & (1 << interrupt as u8) != 0
+    }
+
+    pub fn insert(&mut self, interrupt: Pcf8523Interrupt) {
+        self.0 |= 1 << interrupt as u8;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Pcf8523Interrupt> + '_ {
+        Pcf8523Interrupt::ALL.into_iter().filter(|interrupt| self.contains(*interrupt))
+    }
+
+    pub fn remove(&mut self, interrupt: Pcf8523Interrupt) {
+        self.0 &= !(1 << interrupt as u8);
+    }
+
+    // interrupts whose flags are set in CONTROL_1 to CONTROL_3
+    pub(crate) fn from_flags(controls: &[u8; 3]) -> Self {
+        let mut interrupts = Self::empty();
+        for interrupt in Pcf8523Interrupt::ALL {
+            let (reg, mask) = interrupt.flag_bit();
+            if controls[reg as usize] & mask != 0 { interrupts.insert(interrupt); }
+        }
+        interrupts
+    }
+
+    // clears the flags of these interrupts in CONTROL_1 to CONTROL_3, BLF is read-only and left as is.
+    // Writing 1 leaves a flag unchanged, so every other flag is written as 1 to keep one raised after
+    // controls was read.
+    pub(crate) fn clear_flags(&self, controls: &mut [u8; 3]) {
+        for interrupt in Pcf8523Interrupt::ALL.into_iter().filter(|interrupt| *interrupt != Pcf8523Interrupt::BatteryLow) {
+            let (reg, mask) = interrupt.flag_bit();
+            if self.contains(interrupt) { controls[reg as usize] &= !mask } else { controls[reg as usize] |= mask }
+        }
+    }
+
+    // flags in reg that writing 0 clears, written as 1 by read-modify-write so they stay as they are
+    pub(crate) fn clearable_flags(reg: u8) -> u8 {
+        Pcf8523Interrupt::ALL.into_iter()
+            .filter(|interrupt| *interrupt != Pcf8523Interrupt::BatteryLow)
+            .map(|interrupt| interrupt.flag_bit())
+            .filter(|(flag_reg, _)| *flag_reg == reg)
+            .fold(0, |flags, (_, mask)| flags | mask)
+    }
+
+    // sets or clears the enable bits of these interrupts in CONTROL_1 to CONTROL_3
+    pub(crate) fn set_enabled(&self, controls: &mut [u8; 3], enabled: bool) {
+        for interrupt in self.iter() {
+            let (reg, mask) = interrupt.enable_bit();
+            if enabled { controls[reg as usize] |= mask } else { controls[reg as usize] &= !mask }
+        }
+    }
+}
+impl From<Pcf8523Interrupt> for Pcf8523Interrupts {
+    fn from(interrupt: Pcf8523Interrupt) -> Self {
+        let mut interrupts = Self::empty();
+        interrupts.insert(interrupt);
+        interrupts
+    }
+}
+impl BitOr<Pcf8523Interrupt> for Pcf8523Interrupts {
+    type Output = Self;
+    fn bitor(mut self, interrupt: Pcf8523Interrupt) -> Self {
+        self.insert(interrupt);
+        self
+    }
+}
+impl BitOr for Pcf8523Interrupt {
+    type Output = Pcf8523Interrupts;
+    fn bitor(self, interrupt: Pcf8523Interrupt) -> Pcf8523Interrupts {
+        Pcf8523Interrupts::from(self) | interrupt
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PowerManagement {
     SwitchOverStandardLowDetectionEnabled = 0x0,
     SwitchOverDirectSwitchingLowDetectionEnabled = 0x1,
@@ -41,4 +272,115 @@ pub enum PowerManagement {
     SwitchOverStandardLowDetectionDisabled = 0x4,
     SwitchOverDirectSwitchingLowDetectionDisabled = 0x5,
     SwitchOverDisabledLowDetectionDisabled = 0x7,
-}
\ No newline at end of file
+}
+// 0b011 and 0b110 are not documented
+impl TryFrom<u8> for PowerManagement {
+    type Error = ();
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x0 => Ok(PowerManagement::SwitchOverStandardLowDetectionEnabled),
+            0x1 => Ok(PowerManagement::SwitchOverDirectSwitchingLowDetectionEnabled),
+            0x2 => Ok(PowerManagement::SwitchOverDisabledLowDetectionEnabled),
+            0x4 => Ok(PowerManagement::SwitchOverStandardLowDetectionDisabled),
+            0x5 => Ok(PowerManagement::SwitchOverDirectSwitchingLowDetectionDisabled),
+            0x7 => Ok(PowerManagement::SwitchOverDisabledLowDetectionDisabled),
+            _ => Err(())
+        }
+    }
+}
+
+// OS bit of the seconds register, checked at boot to decide whether the time needs re-syncing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeStatus {
+    Valid,
+    // the oscillator stopped since the time was last set, e.g. power failure without a battery
+    LostSincePowerFailure,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hours_from_24_hour() {
+        assert_eq!(Hours::from_24_hour(0, true), Hours::new(12, Some(Meridiem::AM)));
+        assert_eq!(Hours::from_24_hour(11, true), Hours::new(11, Some(Meridiem::AM)));
+        assert_eq!(Hours::from_24_hour(12, true), Hours::new(12, Some(Meridiem::PM)));
+        assert_eq!(Hours::from_24_hour(23, true), Hours::new(11, Some(Meridiem::PM)));
+        assert_eq!(Hours::from_24_hour(23, false), Hours::new(23, None));
+    }
+
+    #[test]
+    fn hours_round_trip() {
+        for hours in 0..24 {
+            for mode_12hr in [false, true] {
+                let reg = Hours::from_24_hour(hours, mode_12hr).encode();
+                assert_eq!(Hours::decode(reg, mode_12hr).to_24_hour(), hours);
+            }
+        }
+    }
+
+    #[test]
+    fn hours_decode_12_hour_pm() {
+        assert_eq!(Hours::decode(0b11_0010, true), Hours::new(12, Some(Meridiem::PM)));
+    }
+
+    #[test]
+    fn power_management_try_from_round_trip() {
+        for value in 0..8u8 {
+            match PowerManagement::try_from(value) {
+                Ok(pm) => assert_eq!(pm as u8, value),
+                Err(()) => assert!(value == 0b011 || value == 0b110),
+            }
+        }
+    }
+
+    #[test]
+    fn interrupts_bitor() {
+        let interrupts = Pcf8523Interrupt::Alarm | Pcf8523Interrupt::Second;
+        assert!(interrupts.contains(Pcf8523Interrupt::Alarm));
+        assert!(interrupts.contains(Pcf8523Interrupt::Second));
+        assert!(!interrupts.contains(Pcf8523Interrupt::CountdownTimerA));
+    }
+
+    #[test]
+    fn interrupts_from_flags() {
+        let interrupts = Pcf8523Interrupts::from_flags(&[0b1111_1111, 0b1010_0111, 0b1110_1000]);
+        assert_eq!(interrupts, Pcf8523Interrupt::WatchdogTimerA | Pcf8523Interrupt::CountdownTimerB | Pcf8523Interrupt::BatterySwitchOver);
+    }
+
+    #[test]
+    fn interrupts_clearable_flags() {
+        assert_eq!(Pcf8523Interrupts::clearable_flags(PCF8523_CONTROL_1), 0);
+        assert_eq!(Pcf8523Interrupts::clearable_flags(PCF8523_CONTROL_2), 0b1111_1000);
+        assert_eq!(Pcf8523Interrupts::clearable_flags(PCF8523_CONTROL_3), 0b1000);
+    }
+
+    #[test]
+    fn interrupts_clear_flags() {
+        let mut controls = [0b1111_1111, 0b1111_1111, 0b1110_1111];
+        (Pcf8523Interrupt::Alarm | Pcf8523Interrupt::BatterySwitchOver | Pcf8523Interrupt::BatteryLow).clear_flags(&mut controls);
+        assert_eq!(controls, [0b1111_1111, 0b1111_0111, 0b1110_0111]);
+        let mut controls = [0b0000_0000, 0b0000_1000, 0b0000_0000];
+        (Pcf8523Interrupt::Alarm | Pcf8523Interrupt::BatterySwitchOver).clear_flags(&mut controls);
+        assert_eq!(controls, [0b0000_0000, 0b1111_0000, 0b0000_0000]);
+    }
+
+    #[test]
+    fn interrupts_set_enabled() {
+        let mut controls = [0b0000_0010, 0b0000_0000, 0b1110_0000];
+        (Pcf8523Interrupt::Second | Pcf8523Interrupt::CountdownTimerB | Pcf8523Interrupt::BatteryLow).set_enabled(&mut controls, true);
+        assert_eq!(controls, [0b0000_0110, 0b0000_0001, 0b1110_0001]);
+        (Pcf8523Interrupt::Alarm | Pcf8523Interrupt::BatteryLow).set_enabled(&mut controls, false);
+        assert_eq!(controls, [0b0000_0100, 0b0000_0001, 0b1110_0000]);
+    }
+
+    #[test]
+    fn interrupts_iter() {
+        let mut interrupts = Pcf8523Interrupt::CountdownTimerA | Pcf8523Interrupt::Alarm;
+        interrupts.remove(Pcf8523Interrupt::CountdownTimerA);
+        let mut iter = interrupts.iter();
+        assert_eq!(iter.next(), Some(Pcf8523Interrupt::Alarm));
+        assert_eq!(iter.next(), None);
+    }
+}
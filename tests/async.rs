@@ -0,0 +1,122 @@
+#![cfg(feature = "async")]
+
+use embassy_futures::block_on;
+use embedded_hal::i2c::ErrorKind::Other;
+use embedded_hal_mock::eh1::delay::{CheckedDelay, Transaction as DelayTransaction};
+use embedded_hal_mock::eh1::digital::{Edge, Mock as PinMock, State, Transaction as PinTransaction};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+use nxp_pcf8523::datetime::{DateTime, DayOfWeek, Month};
+use nxp_pcf8523::driver::{Pcf8523Async, PCF8523_I2C_ADDRESS};
+use nxp_pcf8523::interrupt::Pcf8523IntAsync;
+use nxp_pcf8523::typedefs::{Pcf8523Error, Pcf8523Interrupt, PowerManagement};
+use nxp_pcf8523::registers::*;
+
+#[test]
+fn clear_second_interrupt_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_2, 0b1001_0111),
+        i2c_reg_write(PCF8523_CONTROL_2, 0b1000_0111),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523Async::new(&mut i2c);
+    block_on(driver.clear_interrupt(Pcf8523Interrupt::Second)).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn get_datetime_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b100_0010, 0b1_0111, 0b10_0011, 0b11_0001, 0b101, 0b1_0010, 0b10_0101].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523Async::new(&mut i2c);
+    let dt = block_on(driver.get_datetime()).unwrap();
+    assert_eq!(dt.seconds(), 42);
+    i2c.done();
+}
+
+#[test]
+fn read_reg_i2c_err() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0101_0110).with_error(Other),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523Async::new(&mut i2c);
+    let err = block_on(driver.read_reg(PCF8523_CONTROL_1)).unwrap_err();
+    assert_eq!(err, Pcf8523Error::I2C(Other));
+    i2c.done();
+}
+
+#[test]
+fn select_power_management_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_3, 0b0001_1010),
+        i2c_reg_write(PCF8523_CONTROL_3, 0b0101_1010),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523Async::new(&mut i2c);
+    block_on(driver.select_power_management(PowerManagement::SwitchOverDisabledLowDetectionEnabled)).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_datetime_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [0b101_1001, 0b1_0101, 0b10, 0b1_0001, 0b11, 0b1_0000, 0b100_0101].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523Async::new(&mut i2c);
+    let datetime = DateTime::new(59, 15, 2, 11, DayOfWeek::Wednesday, Month::October, 45).unwrap();
+    block_on(driver.set_datetime(datetime)).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_datetime_synchronized_to_pin_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b10_0000),
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [0b0, 0b0, 0b0, 0b1, 0b0, 0b1, 0b0].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut pin = PinMock::new(&[PinTransaction::wait_for_edge(Edge::Rising)]);
+    let mut driver = Pcf8523Async::new(&mut i2c);
+    let dt = DateTime::new(0, 0, 0, 1, DayOfWeek::Sunday, Month::January, 0).unwrap();
+    let mut delay = CheckedDelay::new(&[DelayTransaction::async_delay_us(492_126)]);
+    block_on(driver.set_datetime_synchronized_to_pin(dt, &mut pin, &mut delay)).unwrap();
+    i2c.done();
+    pin.done();
+    delay.done();
+}
+
+#[test]
+fn wait_for_event_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b0000_0000, 0b0100_0010, 0b0000_0000].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_2, 0b1011_1010, 0b0000_1000].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut pin = PinMock::new(&[PinTransaction::wait_for_state(State::Low)]);
+    let mut rtc = Pcf8523IntAsync::new(Pcf8523Async::new(&mut i2c), &mut pin);
+    let fired = block_on(rtc.wait_for_event()).unwrap();
+    assert_eq!(fired, Pcf8523Interrupt::CountdownTimerA.into());
+    i2c.done();
+    pin.done();
+}
+
+fn i2c_reg_read(reg: u8, payload: u8) -> I2cTransaction {
+    I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [reg].to_vec(), [payload].to_vec())
+}
+
+fn i2c_reg_write(reg: u8, payload: u8) -> I2cTransaction {
+    I2cTransaction::write(PCF8523_I2C_ADDRESS, [reg, payload].to_vec())
+}
@@ -1,16 +1,41 @@
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, Operation};
 use embedded_hal::i2c::ErrorKind::Other;
-use embedded_hal::i2c::Operation;
+use embedded_hal_mock::eh1::delay::{CheckedDelay, Transaction as DelayTransaction};
+use embedded_hal_mock::eh1::digital::{Mock as PinMock, State, Transaction as PinTransaction};
 use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
-use nxp_pcf8523::datetime::{DateTime, DayOfWeek, Month};
+use nxp_pcf8523::alarm::Alarm;
+use nxp_pcf8523::calibration::DriftMeasurement;
+use nxp_pcf8523::datetime::{DateTime, DateTimeError, DayOfWeek, Month, DEFAULT_CENTURY};
 use nxp_pcf8523::driver::{Pcf8523, PCF8523_I2C_ADDRESS};
-use nxp_pcf8523::typedefs::{Pcf8523Error, Pcf8523Interrupt, PowerManagement};
+use nxp_pcf8523::interrupt::Pcf8523Int;
+use nxp_pcf8523::typedefs::{ClockOutFrequency, Hours, Meridiem, Pcf8523Error, Pcf8523Interrupt, PowerManagement, TimeStatus};
+use nxp_pcf8523::offset::{Offset, OffsetMode};
 use nxp_pcf8523::registers::*;
+use nxp_pcf8523::timer::{TimerAMode, TimerBPulseWidth, TimerInterruptMode, TimerSourceClock};
+
+#[test]
+fn calibrate_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_OFFSET, 0b0000_0001),
+        i2c_reg_write(PCF8523_OFFSET, 0b0111_1111),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    // RTC gained 1 s over 100 000 s with an offset of +1 step applied
+    let start = DateTime::new(0, 0, 0, 1, DayOfWeek::Wednesday, Month::January, 25).unwrap();
+    let end = DateTime::new(41, 46, 3, 2, DayOfWeek::Thursday, Month::January, 25).unwrap();
+    let measurement = DriftMeasurement::new(5_000, &start, 100_005_000, &end).unwrap();
+    let calibration = driver.calibrate(measurement, OffsetMode::EveryTwoHours).unwrap();
+    assert_eq!(calibration.offset().value(), -1);
+    assert!(!calibration.exceeds_range());
+    i2c.done();
+}
 
 #[test]
 fn clear_bsf_interrupt_ok() {
     let expectations = [
-        i2c_reg_read(PCF8523_CONTROL_3, 0b1100_0111),
-        i2c_reg_write(PCF8523_CONTROL_3, 0b1100_0011),
+        i2c_reg_read(PCF8523_CONTROL_3, 0b1100_1111),
+        i2c_reg_write(PCF8523_CONTROL_3, 0b1100_0111),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
@@ -42,6 +67,34 @@ fn clear_countdown_timer_b_interrupt_ok() {
     i2c.done();
 }
 
+#[test]
+fn clear_interrupts_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b0000_0000, 0b0111_1011, 0b1000_1010].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_2, 0b1010_0011, 0b1000_0010].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let interrupts = Pcf8523Interrupt::Alarm | Pcf8523Interrupt::CountdownTimerA | Pcf8523Interrupt::Second | Pcf8523Interrupt::BatterySwitchOver;
+    driver.clear_interrupts(interrupts).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn clear_oscillator_stop_flag_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b1000_0000),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b1010_0000),
+        i2c_reg_read(PCF8523_SECONDS, 0b1101_0010),
+        i2c_reg_write(PCF8523_SECONDS, 0b0101_0010),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b1000_0000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.clear_oscillator_stop_flag().unwrap();
+    i2c.done();
+}
+
 #[test]
 fn clear_second_interrupt_ok() {
     let expectations = [
@@ -65,6 +118,66 @@ fn clear_watchdog_timer_a_interrupt_ok() {
     i2c.done();
 }
 
+#[test]
+fn disable_alarm_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0000_0110),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_MINUTE_ALARM, 0x80, 0x80, 0x80, 0x80].to_vec()),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0000_0100),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.disable_alarm().unwrap();
+    i2c.done();
+}
+
+#[test]
+fn enable_cache_ok() {
+    let expectations = [
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec()),
+        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0000_1000].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_TMR_CLKOUT_CTRL].to_vec()),
+        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0011_1000, 0b10, 0x20, 0b11, 0x10].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+        // served from the cache, only the writes go out
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0010_1000),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b1010_1000),
+        i2c_reg_write(PCF8523_TMR_CLKOUT_CTRL, 0b0010_0000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.enable_cache().unwrap();
+    driver.freeze_rtc_time_circuits(true).unwrap();
+    driver.select_oscillator_capacitor(true).unwrap();
+    assert!(driver.get_hour_mode_12hr().unwrap());
+    driver.set_clkout(ClockOutFrequency::Hz1024).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn enable_cache_software_reset_invalidates_ok() {
+    let expectations = [
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec()),
+        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0000_1000].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_TMR_CLKOUT_CTRL].to_vec()),
+        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0011_1000, 0b10, 0x20, 0b11, 0x10].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0101_1000),
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0000_0000),
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b1111_1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.enable_cache().unwrap();
+    driver.software_reset().unwrap();
+    assert!(!driver.get_hour_mode_12hr().unwrap());
+    assert!(!driver.get_hour_mode_12hr().unwrap());
+    assert_eq!(driver.get_clkout().unwrap(), ClockOutFrequency::Disabled);
+    i2c.done();
+}
+
 #[test]
 fn freeze_rtc_time_circuits_ok() {
     let expectations = [
@@ -77,6 +190,62 @@ fn freeze_rtc_time_circuits_ok() {
     i2c.done();
 }
 
+#[test]
+fn get_alarm_disabled_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_MINUTE_ALARM].to_vec(), [0x80, 0x80, 0x80, 0x80].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let alarm = driver.get_alarm().unwrap();
+    assert_eq!(alarm, Alarm::default());
+    i2c.done();
+}
+
+#[test]
+fn get_alarm_invalid_weekday_err() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_MINUTE_ALARM].to_vec(), [0x80, 0x80, 0x80, 0b111].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let err = driver.get_alarm().unwrap_err();
+    assert_eq!(err, Pcf8523Error::InvalidRegisterValue(PCF8523_WEEKDAY_ALARM));
+    i2c.done();
+}
+
+#[test]
+fn get_alarm_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_MINUTE_ALARM].to_vec(), [0b0011_0000, 0b0000_0111, 0x80, 0b001].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let alarm = driver.get_alarm().unwrap();
+    assert_eq!(alarm.minute(), Some(30));
+    assert_eq!(alarm.hour(), Some(7));
+    assert_eq!(alarm.day(), None);
+    assert_eq!(alarm.weekday(), Some(DayOfWeek::Monday));
+    i2c.done();
+}
+
+#[test]
+fn get_battery_status_low_detection_disabled_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_3, 0b1010_1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let status = driver.get_battery_status().unwrap();
+    assert!(!status.battery_low());
+    assert!(status.switch_over_occurred());
+    assert!(!status.low_detection_enabled());
+    i2c.done();
+}
+
 #[test]
 fn get_battery_status_low_false_ok() {
     let expectations = [
@@ -84,20 +253,35 @@ fn get_battery_status_low_false_ok() {
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let low = driver.get_battery_status().unwrap();
-    assert!(!low);
+    let status = driver.get_battery_status().unwrap();
+    assert!(!status.battery_low());
+    assert!(!status.switch_over_occurred());
+    assert!(status.low_detection_enabled());
     i2c.done();
 }
 
 #[test]
 fn get_battery_status_low_true_ok() {
     let expectations = [
-        i2c_reg_read(PCF8523_CONTROL_3, 0b100),
+        i2c_reg_read(PCF8523_CONTROL_3, 0b0010_0100),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let status = driver.get_battery_status().unwrap();
+    assert!(status.battery_low());
+    assert!(status.low_detection_enabled());
+    i2c.done();
+}
+
+#[test]
+fn get_clkout_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b0011_0011),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let low = driver.get_battery_status().unwrap();
-    assert!(low);
+    let frequency = driver.get_clkout().unwrap();
+    assert_eq!(frequency, ClockOutFrequency::Hz1);
     i2c.done();
 }
 
@@ -126,54 +310,106 @@ fn get_clock_integrity_guaranteed_true_ok() {
 }
 
 #[test]
-fn get_correction_interrupt_generated_false_ok() {
+fn get_correction_interrupt_enabled_false_ok() {
     let expectations = [
         i2c_reg_read(PCF8523_CONTROL_1, 0b0),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let guaranteed = driver.get_correction_interrupt_generated().unwrap();
-    assert!(!guaranteed);
+    let enabled = driver.get_correction_interrupt_enabled().unwrap();
+    assert!(!enabled);
     i2c.done();
 }
 
 #[test]
-fn get_correction_interrupt_generated_true_ok() {
+fn get_correction_interrupt_enabled_true_ok() {
     let expectations = [
         i2c_reg_read(PCF8523_CONTROL_1, 0b1),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let guaranteed = driver.get_correction_interrupt_generated().unwrap();
-    assert!(guaranteed);
+    let enabled = driver.get_correction_interrupt_enabled().unwrap();
+    assert!(enabled);
+    i2c.done();
+}
+
+#[test]
+fn get_datetime_checked_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b0, 0b0, 0b0, 0b1, 0b11, 0b1, 0b10_0101].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    assert!(driver.get_datetime_checked(DEFAULT_CENTURY).is_ok());
+    i2c.done();
+}
+
+#[test]
+fn get_datetime_checked_weekday_mismatch_err() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b0, 0b0, 0b0, 0b1, 0b10, 0b1, 0b10_0101].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let err = driver.get_datetime_checked(DEFAULT_CENTURY).unwrap_err();
+    assert_eq!(err, Pcf8523Error::DateTime(DateTimeError::InvalidWeekday));
     i2c.done();
 }
 
 #[test]
 fn get_datetime_ok() {
     let expectations = [
-        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
         // all payloads are bcd-encoded
-        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec()),
-        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0].to_vec()),
-        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_MINUTES].to_vec()),
-        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0].to_vec()),
-        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_HOURS].to_vec()),
-        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0].to_vec()),
-        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_DAYS].to_vec()),
-        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0].to_vec()),
-        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_WEEKDAYS].to_vec()),
-        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0].to_vec()),
-        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_MONTHS].to_vec()),
-        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b1].to_vec()),
-        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_YEARS].to_vec()),
-        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0].to_vec()),
-        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b0, 0b0, 0b0, 0b0, 0b0, 0b1, 0b0].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = driver.get_datetime().unwrap();
+    assert_eq!(dt.seconds(), 0);
+    i2c.done();
+}
+
+#[test]
+fn get_datetime_12_hour_mode_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b1000),
+        // 7 PM
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b0, 0b0, 0b10_0111, 0b1, 0b0, 0b1, 0b0].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = driver.get_datetime().unwrap();
+    let expected = DateTime::new(0, 0, 19, 1, DayOfWeek::Sunday, Month::January, 0).unwrap();
+    assert_eq!(dt, expected);
+    i2c.done();
+}
+
+#[test]
+fn get_datetime_bcd_decoded_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b100_0010, 0b1_0111, 0b10_0011, 0b11_0001, 0b101, 0b1_0010, 0b10_0101].to_vec()),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
     let dt = driver.get_datetime().unwrap();
-    assert_eq!(dt.seconds, 0);
+    assert_eq!(dt.seconds(), 42);
+    i2c.done();
+}
+
+#[test]
+fn get_datetime_oscillator_stop_masked_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b1100_0010, 0b0, 0b0, 0b1, 0b0, 0b1, 0b0].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = driver.get_datetime().unwrap();
+    assert_eq!(dt.seconds(), 42);
     i2c.done();
 }
 
@@ -206,6 +442,43 @@ fn get_day_of_week_ok() {
     i2c.done();
 }
 
+#[test]
+fn get_hour_mode_12hr_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b1000_1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    assert!(driver.get_hour_mode_12hr().unwrap());
+    i2c.done();
+}
+
+#[test]
+fn get_hours_12_hour_mode_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b1000),
+        i2c_reg_read(PCF8523_HOURS, 0b01_0010),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let hours = driver.get_hours().unwrap();
+    assert_eq!(hours, Hours::new(12, Some(Meridiem::AM)));
+    assert_eq!(hours.to_24_hour(), 0);
+    i2c.done();
+}
+
+#[test]
+fn get_hours_24_hour_mode_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        i2c_reg_read(PCF8523_HOURS, 0b10_0010),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    assert_eq!(driver.get_hours().unwrap(), Hours::new(22, None));
+    i2c.done();
+}
+
 #[test]
 fn get_interrupt_enabled_alarm_false() {
     let expectations = [
@@ -230,6 +503,18 @@ fn get_interrupt_enabled_alarm_true() {
     i2c.done();
 }
 
+#[test]
+fn get_interrupt_enabled_battery_low_true() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_3, 0b1),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let enabled = driver.get_interrupt_enabled(Pcf8523Interrupt::BatteryLow).unwrap();
+    assert!(enabled);
+    i2c.done();
+}
+
 #[test]
 fn get_interrupt_enabled_battery_switch_over_false() {
     let expectations = [
@@ -374,148 +659,903 @@ fn get_month_ok() {
     i2c.done();
 }
 
+#[cfg(feature = "chrono")]
 #[test]
-fn get_seconds_ok() {
+fn get_naive_datetime_invalid_day_err() {
+    use nxp_pcf8523::datetime::DateTimeError;
     let expectations = [
-        i2c_reg_read(PCF8523_SECONDS, 0b0101_1000),
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b0, 0b0, 0b0, 0b11_0000, 0b0, 0b10, 0b10_0101].to_vec()),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let seconds = driver.get_seconds().unwrap();
-    assert_eq!(seconds, 58u8);
+    let err = driver.get_naive_datetime().unwrap_err();
+    assert_eq!(err, Pcf8523Error::DateTime(DateTimeError::InvalidDay));
     i2c.done();
 }
 
 #[test]
-fn get_year_ok() {
+fn get_offset_ok() {
     let expectations = [
-        i2c_reg_read(PCF8523_YEARS, 0b10_0101),
+        i2c_reg_read(PCF8523_OFFSET, 0b1111_1101),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let year = driver.get_year().unwrap();
-    assert_eq!(year, 25u8);
+    let offset = driver.get_offset().unwrap();
+    assert_eq!(offset.mode(), OffsetMode::EveryMinute);
+    assert_eq!(offset.value(), -3);
     i2c.done();
 }
 
 #[test]
-fn read_reg_i2c_err() {
+fn get_power_management_ok() {
     let expectations = [
-        i2c_reg_read(PCF8523_CONTROL_1, 0b0101_0110).with_error(Other),
+        i2c_reg_read(PCF8523_CONTROL_3, 0b1010_1011),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let err = driver.read_reg(PCF8523_CONTROL_1).unwrap_err();
-    assert_eq!(err, Pcf8523Error::I2C(Other));
+    let pm = driver.get_power_management().unwrap();
+    assert_eq!(pm, PowerManagement::SwitchOverDirectSwitchingLowDetectionDisabled);
     i2c.done();
 }
 
 #[test]
-fn read_reg_ok() {
+fn get_power_management_undocumented_err() {
     let expectations = [
-        i2c_reg_read(PCF8523_CONTROL_1, 0b0101_0110),
+        i2c_reg_read(PCF8523_CONTROL_3, 0b0110_0000),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let val = driver.read_reg(PCF8523_CONTROL_1).unwrap();
-    assert_eq!(val, 0b0101_0110);
+    let err = driver.get_power_management().unwrap_err();
+    assert_eq!(err, Pcf8523Error::InvalidRegisterValue(PCF8523_CONTROL_3));
     i2c.done();
 }
 
 #[test]
-fn reset_ok() {
+fn get_seconds_ok() {
     let expectations = [
-        i2c_reg_write(PCF8523_CONTROL_1, 0b0101_1000),
+        i2c_reg_read(PCF8523_SECONDS, 0b0101_1000),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    driver.software_reset().unwrap();
+    let seconds = driver.get_seconds().unwrap();
+    assert_eq!(seconds, 58u8);
     i2c.done();
 }
 
 #[test]
-fn select_oscillator_capacitor_ok() {
+fn get_time_status_lost_ok() {
     let expectations = [
-        i2c_reg_read(PCF8523_CONTROL_1, 0b0101_0110),
-        i2c_reg_write(PCF8523_CONTROL_1, 0b1101_0110),
+        i2c_reg_read(PCF8523_SECONDS, 0b1000_0000),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    driver.select_oscillator_capacitor(true).unwrap();
+    assert_eq!(driver.get_time_status().unwrap(), TimeStatus::LostSincePowerFailure);
     i2c.done();
 }
 
 #[test]
-fn select_hour_mode_ok() {
+fn get_time_status_valid_ok() {
     let expectations = [
-        i2c_reg_read(PCF8523_CONTROL_1, 0b1001_0010),
-        i2c_reg_write(PCF8523_CONTROL_1, 0b1001_1010),
+        i2c_reg_read(PCF8523_SECONDS, 0b0101_1001),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    driver.select_hour_mode(true).unwrap();
+    assert_eq!(driver.get_time_status().unwrap(), TimeStatus::Valid);
     i2c.done();
 }
 
 #[test]
-fn select_power_management_ok() {
+fn get_timer_a_clock_ok() {
     let expectations = [
-        i2c_reg_read(PCF8523_CONTROL_3, 0b0001_1010),
-        i2c_reg_write(PCF8523_CONTROL_3, 0b0101_1010),
+        i2c_reg_read(PCF8523_TMR_A_FREQ_CTRL, 0b011),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    driver.select_power_management(PowerManagement::SwitchOverDisabledLowDetectionEnabled).unwrap();
+    let clock = driver.get_timer_a_clock().unwrap();
+    assert_eq!(clock, TimerSourceClock::PerMinute);
     i2c.done();
 }
 
 #[test]
-fn set_datetime_ok() {
+fn get_timer_a_mode_ok() {
     let expectations = [
-        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
-        i2c_reg_write(PCF8523_SECONDS, 0b11_1011),
-        i2c_reg_write(PCF8523_MINUTES, 0b1111),
-        i2c_reg_write(PCF8523_HOURS, 0b10),
-        i2c_reg_write(PCF8523_DAYS, 0b1011),
-        i2c_reg_write(PCF8523_WEEKDAYS, 0b11),
-        i2c_reg_write(PCF8523_MONTHS, 0b1010),
-        i2c_reg_write(PCF8523_YEARS, 0b10_1101),
-        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b0011_1101),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let datetime = DateTime::new(59, 15, 2, 11, DayOfWeek::Wednesday, Month::October, 45).unwrap();
-    driver.set_datetime(datetime).unwrap();
+    let mode = driver.get_timer_a_mode().unwrap();
+    assert_eq!(mode, TimerAMode::Watchdog);
     i2c.done();
 }
 
 #[test]
-fn write_reg_i2c_err() {
+fn get_timer_a_value_ok() {
     let expectations = [
-        i2c_reg_write(PCF8523_CONTROL_1, 0b0101_0110).with_error(Other),
+        i2c_reg_read(PCF8523_TMR_A_REG, 42),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    let err = driver.write_reg(PCF8523_CONTROL_1, 0b0101_0110).unwrap_err();
-    assert_eq!(err, Pcf8523Error::I2C(Other));
+    let value = driver.get_timer_a_value().unwrap();
+    assert_eq!(value, 42);
     i2c.done();
 }
 
 #[test]
-fn write_reg_ok() {
+fn get_timer_b_clock_ok() {
     let expectations = [
-        i2c_reg_write(PCF8523_CONTROL_1, 0b0101_0010),
+        i2c_reg_read(PCF8523_TMR_B_FREQ_CTRL, 0b0101_0001),
     ];
     let mut i2c = I2cMock::new(&expectations);
     let mut driver = Pcf8523::new(&mut i2c);
-    driver.write_reg(PCF8523_CONTROL_1, 0b0101_0010).unwrap();
+    let clock = driver.get_timer_b_clock().unwrap();
+    assert_eq!(clock, TimerSourceClock::Hz64);
     i2c.done();
 }
 
-fn i2c_reg_read(reg: u8, payload: u8) -> I2cTransaction {
-    I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [reg].to_vec(), [payload].to_vec())
+#[test]
+fn get_timer_b_enabled_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b0011_1001),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let enabled = driver.get_timer_b_enabled().unwrap();
+    assert!(enabled);
+    i2c.done();
 }
 
-fn i2c_reg_write(reg: u8, payload: u8) -> I2cTransaction {
-    I2cTransaction::write(PCF8523_I2C_ADDRESS, [reg, payload].to_vec())
+#[test]
+fn get_timer_b_interrupt_mode_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b0111_1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let mode = driver.get_timer_b_interrupt_mode().unwrap();
+    assert_eq!(mode, TimerInterruptMode::Pulsed);
+    i2c.done();
+}
+
+#[test]
+fn get_timer_b_pulse_width_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_B_FREQ_CTRL, 0b0101_0001),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let width = driver.get_timer_b_pulse_width().unwrap();
+    assert_eq!(width, TimerBPulseWidth::Ms156_25);
+    i2c.done();
+}
+
+#[test]
+fn get_timer_b_value_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_B_REG, 7),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let value = driver.get_timer_b_value().unwrap();
+    assert_eq!(value, 7);
+    i2c.done();
+}
+
+#[test]
+fn get_year_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_YEARS, 0b10_0101),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let year = driver.get_year().unwrap();
+    assert_eq!(year, 25u8);
+    i2c.done();
+}
+
+#[test]
+fn invalidate_cache_ok() {
+    let expectations = [
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec()),
+        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0000_0000].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_TMR_CLKOUT_CTRL].to_vec()),
+        I2cTransaction::read(PCF8523_I2C_ADDRESS, [0b0010_0000, 0b10, 0x20, 0b11, 0x10].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+        // chip lost power and came back with the reset value
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b0000_0000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.enable_cache().unwrap();
+    assert_eq!(driver.get_clkout().unwrap(), ClockOutFrequency::Hz1024);
+    driver.invalidate_cache();
+    assert_eq!(driver.get_clkout().unwrap(), ClockOutFrequency::Hz32768);
+    assert_eq!(driver.get_clkout().unwrap(), ClockOutFrequency::Hz32768);
+    i2c.done();
+}
+
+#[test]
+fn pending_interrupts_none_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b1111_1111, 0b0000_0111, 0b1111_0011].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    assert!(driver.pending_interrupts().unwrap().is_empty());
+    i2c.done();
+}
+
+#[test]
+fn pending_interrupts_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b0000_0110, 0b1011_0111, 0b0000_1000].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let pending = driver.pending_interrupts().unwrap();
+    assert_eq!(pending, Pcf8523Interrupt::WatchdogTimerA | Pcf8523Interrupt::CountdownTimerB | Pcf8523Interrupt::Second | Pcf8523Interrupt::BatterySwitchOver);
+    i2c.done();
+}
+
+#[test]
+fn read_control1_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0010_1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let control = driver.read_control1().unwrap();
+    assert!(control.stop());
+    assert!(control.hour_mode_12hr());
+    assert!(!control.cap_sel());
+    i2c.done();
+}
+
+#[test]
+fn read_controls_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b1000_0000, 0b0100_0010, 0b1000_0001].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let (control_1, control_2, control_3) = driver.read_controls().unwrap();
+    assert!(control_1.cap_sel());
+    assert!(control_2.ctaf() && control_2.ctaie());
+    assert_eq!(control_3.pm(), Some(PowerManagement::SwitchOverStandardLowDetectionDisabled));
+    assert!(control_3.blie());
+    i2c.done();
+}
+
+#[test]
+fn read_reg_i2c_err() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0101_0110).with_error(Other),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let err = driver.read_reg(PCF8523_CONTROL_1).unwrap_err();
+    assert_eq!(err, Pcf8523Error::I2C(Other));
+    i2c.done();
+}
+
+#[test]
+fn read_reg_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0101_0110),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let val = driver.read_reg(PCF8523_CONTROL_1).unwrap();
+    assert_eq!(val, 0b0101_0110);
+    i2c.done();
+}
+
+#[test]
+fn read_regs_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_TMR_A_FREQ_CTRL].to_vec(), [0b10, 0x3c].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let mut regs = [0u8; 2];
+    driver.read_regs(PCF8523_TMR_A_FREQ_CTRL, &mut regs).unwrap();
+    assert_eq!(regs, [0b10, 0x3c]);
+    i2c.done();
+}
+
+#[test]
+fn reset_ok() {
+    let expectations = [
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0101_1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.software_reset().unwrap();
+    i2c.done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn rtcc_datetime_ok() {
+    use rtcc::{DateTimeAccess, NaiveDate};
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b101_1001, 0b1_0101, 0b10, 0b1_0001, 0b10, 0b1_0000, 0b100_0101].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = driver.datetime().unwrap();
+    assert_eq!(dt, NaiveDate::from_ymd_opt(2045, 10, 11).unwrap().and_hms_opt(2, 15, 59).unwrap());
+    i2c.done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn rtcc_set_datetime_ok() {
+    use rtcc::{DateTimeAccess, NaiveDate};
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [0b101_1001, 0b1_0101, 0b10, 0b1_0001, 0b11, 0b1_0000, 0b100_0101].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = NaiveDate::from_ymd_opt(2045, 10, 11).unwrap().and_hms_opt(2, 15, 59).unwrap();
+    DateTimeAccess::set_datetime(&mut driver, &dt).unwrap();
+    i2c.done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn rtcc_set_datetime_invalid_year_err() {
+    use nxp_pcf8523::datetime::DateTimeError;
+    use rtcc::{DateTimeAccess, NaiveDate};
+    let mut i2c = I2cMock::new(&[]);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = NaiveDate::from_ymd_opt(2100, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let err = DateTimeAccess::set_datetime(&mut driver, &dt).unwrap_err();
+    assert_eq!(err, Pcf8523Error::DateTime(DateTimeError::InvalidYear));
+    i2c.done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn rtcc_hours_pm_ok() {
+    use rtcc::{Hours, Rtcc};
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b1000),
+        i2c_reg_read(PCF8523_HOURS, 0b11_0001),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    assert_eq!(driver.hours().unwrap(), Hours::PM(11));
+    i2c.done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn rtcc_set_hours_pm_ok() {
+    use rtcc::{Hours, Rtcc};
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        // a disabled hour alarm is written back unchanged
+        i2c_reg_read(PCF8523_HOUR_ALARM, 0b1000_0000),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b10_1000),
+        i2c_reg_write(PCF8523_HOURS, 0b11_0001),
+        i2c_reg_write(PCF8523_HOUR_ALARM, 0b1000_0000),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_hours(Hours::PM(11)).unwrap();
+    i2c.done();
+}
+
+#[cfg(feature = "rtcc")]
+#[test]
+fn rtcc_weekday_ok() {
+    use rtcc::Rtcc;
+    let expectations = [
+        i2c_reg_read(PCF8523_WEEKDAYS, 0b0),
+        i2c_reg_write(PCF8523_WEEKDAYS, 0b110),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    assert_eq!(driver.weekday().unwrap(), 1);
+    driver.set_weekday(7).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn select_oscillator_capacitor_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0101_0110),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b1101_0110),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.select_oscillator_capacitor(true).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn select_hour_mode_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b1001_0010),
+        i2c_reg_read(PCF8523_HOURS, 0b10_0011),
+        i2c_reg_read(PCF8523_HOUR_ALARM, 0b1_1001),
+        // STOP is held while the hours are converted
+        i2c_reg_write(PCF8523_CONTROL_1, 0b1011_1010),
+        // 23 becomes 11 PM
+        i2c_reg_write(PCF8523_HOURS, 0b11_0001),
+        // the 19 alarm becomes 7 PM
+        i2c_reg_write(PCF8523_HOUR_ALARM, 0b10_0111),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b1001_1010),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.select_hour_mode(true).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn select_hour_mode_i2c_err_restarts() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0000_1000),
+        i2c_reg_read(PCF8523_HOURS, 0b11_0001),
+        i2c_reg_read(PCF8523_HOUR_ALARM, 0b1000_0000),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0010_0000),
+        i2c_reg_write(PCF8523_HOURS, 0b10_0011).with_error(Other),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0000_0000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    assert_eq!(driver.select_hour_mode(false).unwrap_err(), Pcf8523Error::I2C(Other));
+    i2c.done();
+}
+
+#[test]
+fn select_hour_mode_unchanged_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0000_1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.select_hour_mode(true).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn select_power_management_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_3, 0b0001_1010),
+        i2c_reg_write(PCF8523_CONTROL_3, 0b0101_1010),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.select_power_management(PowerManagement::SwitchOverDisabledLowDetectionEnabled).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn select_timer_a_mode_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b0011_1001),
+        i2c_reg_write(PCF8523_TMR_CLKOUT_CTRL, 0b0011_1011),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.select_timer_a_mode(TimerAMode::Countdown).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn select_timer_b_enabled_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b0011_1010),
+        i2c_reg_write(PCF8523_TMR_CLKOUT_CTRL, 0b0011_1011),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.select_timer_b_enabled(true).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn select_timer_b_interrupt_mode_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b1011_1011),
+        i2c_reg_write(PCF8523_TMR_CLKOUT_CTRL, 0b1111_1011),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.select_timer_b_interrupt_mode(TimerInterruptMode::Pulsed).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_alarm_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0000_0100),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_MINUTE_ALARM, 0b0000_0000, 0b0000_0110, 0x80, 0x80].to_vec()),
+        // AF left from an earlier match is cleared before AIE is set, the other flags are written as 1
+        i2c_reg_read(PCF8523_CONTROL_2, 0b0000_1010),
+        i2c_reg_write(PCF8523_CONTROL_2, 0b1111_0010),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0000_0110),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let alarm = Alarm::new(Some(0), Some(6), None, None).unwrap();
+    driver.set_alarm(alarm).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_clkout_disabled_preserves_timer_bits_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b1100_0101),
+        i2c_reg_write(PCF8523_TMR_CLKOUT_CTRL, 0b1111_1101),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_clkout(ClockOutFrequency::Disabled).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_clkout_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_TMR_CLKOUT_CTRL, 0b0011_1011),
+        i2c_reg_write(PCF8523_TMR_CLKOUT_CTRL, 0b0010_0011),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_clkout(ClockOutFrequency::Hz1024).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_correction_interrupt_enabled_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0000_1010),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0000_1011),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_correction_interrupt_enabled(true).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_datetime_12_hour_mode_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b1000),
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec()),
+        // 12 PM
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [0b0, 0b0, 0b11_0010, 0b1, 0b0, 0b1, 0b0].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let datetime = DateTime::new(0, 0, 12, 1, DayOfWeek::Sunday, Month::January, 0).unwrap();
+    driver.set_datetime(datetime).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_datetime_invalid_err() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        // seconds of 85 read back after a power loss
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec(), [0b0111_1111, 0b0, 0b0, 0b1, 0b0, 0b1, 0b0].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = driver.get_datetime().unwrap();
+    assert_eq!(driver.set_datetime(dt).unwrap_err(), Pcf8523Error::DateTime(DateTimeError::InvalidSeconds));
+    i2c.done();
+}
+
+#[test]
+fn set_datetime_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec()),
+        // all payloads are bcd-encoded
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [0b101_1001, 0b1_0101, 0b10, 0b1_0001, 0b11, 0b1_0000, 0b100_0101].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let datetime = DateTime::new(59, 15, 2, 11, DayOfWeek::Wednesday, Month::October, 45).unwrap();
+    driver.set_datetime(datetime).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_datetime_synchronized_i2c_err_restarts() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b10_0000),
+        // the time burst fails in FailingTransaction
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(FailingTransaction(&mut i2c));
+    let mut released = false;
+    let err = driver.set_datetime_synchronized(DateTime::default(), || released = true).unwrap_err();
+    assert_eq!(err, Pcf8523Error::I2C(Other));
+    assert!(!released);
+    i2c.done();
+}
+
+#[test]
+fn set_datetime_synchronized_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b1000),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b10_1000),
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [0b0, 0b0, 0b10_0001, 0b1, 0b0, 0b1, 0b0].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b1000),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = DateTime::new(0, 0, 13, 1, DayOfWeek::Sunday, Month::January, 0).unwrap();
+    let mut released = false;
+    driver.set_datetime_synchronized(dt, || released = true).unwrap();
+    assert!(released);
+    i2c.done();
+}
+
+#[test]
+fn set_datetime_synchronized_to_pin_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b10_0000),
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_SECONDS].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [0b0, 0b0, 0b0, 0b1, 0b0, 0b1, 0b0].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0),
+    ];
+    let pin_expectations = [
+        PinTransaction::get(State::High),
+        PinTransaction::get(State::Low),
+        PinTransaction::get(State::Low),
+        PinTransaction::get(State::High),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut pin = PinMock::new(&pin_expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let dt = DateTime::new(0, 0, 0, 1, DayOfWeek::Sunday, Month::January, 0).unwrap();
+    let mut delay = CheckedDelay::new(&[DelayTransaction::delay_us(492_126)]);
+    driver.set_datetime_synchronized_to_pin(dt, &mut pin, &mut delay).unwrap();
+    i2c.done();
+    pin.done();
+    delay.done();
+}
+
+#[test]
+fn set_interrupt_enabled_battery_low_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_3, 0b1110_0010),
+        i2c_reg_write(PCF8523_CONTROL_3, 0b1110_1011),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_interrupt_enabled(Pcf8523Interrupt::BatteryLow, true).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_interrupt_enabled_countdown_timer_a_false_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_2, 0b0000_0111),
+        // flags are written as 1 so none raised since the read is cleared
+        i2c_reg_write(PCF8523_CONTROL_2, 0b1111_1101),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_interrupt_enabled(Pcf8523Interrupt::CountdownTimerA, false).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_interrupts_enabled_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b0000_0000, 0b0000_0000, 0b1110_0000].to_vec()),
+        // flags read as 0 are written as 1 so none raised since the read is cleared
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1, 0b0000_0110, 0b1111_1100, 0b1110_1010].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let interrupts = Pcf8523Interrupt::Alarm | Pcf8523Interrupt::Second | Pcf8523Interrupt::WatchdogTimerA | Pcf8523Interrupt::BatterySwitchOver;
+    driver.set_interrupts_enabled(interrupts, true).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_offset_ok() {
+    let expectations = [
+        i2c_reg_write(PCF8523_OFFSET, 0b0000_0101),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_offset(Offset::new(OffsetMode::EveryTwoHours, 5).unwrap()).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_offset_with_correction_pulses_ok() {
+    let expectations = [
+        i2c_reg_write(PCF8523_OFFSET, 0b1111_1101),
+        i2c_reg_read(PCF8523_CONTROL_1, 0b0000_0000),
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0000_0001),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_offset_with_correction_pulses(Offset::new(OffsetMode::EveryMinute, -3).unwrap()).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_timer_a_ok() {
+    let expectations = [
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_TMR_A_FREQ_CTRL, 0b010, 30].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_timer_a(TimerSourceClock::Hz1, 30).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn set_timer_b_ok() {
+    let expectations = [
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_TMR_B_FREQ_CTRL, 0b0111_0000, 200].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.set_timer_b(TimerSourceClock::Hz4096, TimerBPulseWidth::Ms218_75, 200).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn wait_for_event_keeps_unreported_flags_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b0000_0000, 0b0000_1000, 0b0000_0000].to_vec()),
+        // flags clear when read are written as 1 so one raised before the write is not lost
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_2, 0b1111_0000, 0b0000_1000].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut pin = PinMock::new(&[PinTransaction::get(State::Low)]);
+    let mut rtc = Pcf8523Int::new(Pcf8523::new(&mut i2c), &mut pin);
+    let fired = rtc.wait_for_event().unwrap();
+    assert_eq!(fired, Pcf8523Interrupt::Alarm.into());
+    i2c.done();
+    pin.done();
+}
+
+#[test]
+fn wait_for_event_no_flags_waits_again() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b0000_0000, 0b0000_0010, 0b0000_0000].to_vec()),
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b0000_0000, 0b1000_0100, 0b0000_0000].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_2, 0b0111_1100, 0b0000_1000].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut pin = PinMock::new(&[PinTransaction::get(State::Low), PinTransaction::get(State::Low)]);
+    let mut rtc = Pcf8523Int::new(Pcf8523::new(&mut i2c), &mut pin);
+    let fired = rtc.wait_for_event().unwrap();
+    assert_eq!(fired, Pcf8523Interrupt::WatchdogTimerA.into());
+    i2c.done();
+    pin.done();
+}
+
+#[test]
+fn wait_for_event_ok() {
+    let expectations = [
+        I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_1].to_vec(), [0b0000_0000, 0b0001_1010, 0b0000_1000].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_CONTROL_2, 0b1110_0010, 0b0000_0000].to_vec()),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut pin = PinMock::new(&[PinTransaction::get(State::High), PinTransaction::get(State::Low)]);
+    let mut rtc = Pcf8523Int::new(Pcf8523::new(&mut i2c), &mut pin);
+    let fired = rtc.wait_for_event().unwrap();
+    assert_eq!(fired, Pcf8523Interrupt::Alarm | Pcf8523Interrupt::Second | Pcf8523Interrupt::BatterySwitchOver);
+    i2c.done();
+    pin.done();
+}
+
+#[test]
+fn write_control3_ok() {
+    let expectations = [
+        i2c_reg_read(PCF8523_CONTROL_3, 0b1110_0000),
+        i2c_reg_write(PCF8523_CONTROL_3, 0b0000_0010),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let mut control = driver.read_control3().unwrap();
+    control.set_pm(PowerManagement::SwitchOverStandardLowDetectionEnabled);
+    control.set_bsie(true);
+    driver.write_control3(control).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn write_reg_i2c_err() {
+    let expectations = [
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0101_0110).with_error(Other),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    let err = driver.write_reg(PCF8523_CONTROL_1, 0b0101_0110).unwrap_err();
+    assert_eq!(err, Pcf8523Error::I2C(Other));
+    i2c.done();
+}
+
+#[test]
+fn write_reg_ok() {
+    let expectations = [
+        i2c_reg_write(PCF8523_CONTROL_1, 0b0101_0010),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.write_reg(PCF8523_CONTROL_1, 0b0101_0010).unwrap();
+    i2c.done();
+}
+
+#[test]
+fn write_regs_ok() {
+    let expectations = [
+        I2cTransaction::transaction_start(PCF8523_I2C_ADDRESS),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [PCF8523_MINUTE_ALARM].to_vec()),
+        I2cTransaction::write(PCF8523_I2C_ADDRESS, [0x80, 0x80, 0x80, 0x80].to_vec()),
+        I2cTransaction::transaction_end(PCF8523_I2C_ADDRESS),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let mut driver = Pcf8523::new(&mut i2c);
+    driver.write_regs(PCF8523_MINUTE_ALARM, &[0x80, 0x80, 0x80, 0x80]).unwrap();
+    i2c.done();
+}
+
+fn i2c_reg_read(reg: u8, payload: u8) -> I2cTransaction {
+    I2cTransaction::write_read(PCF8523_I2C_ADDRESS, [reg].to_vec(), [payload].to_vec())
+}
+
+fn i2c_reg_write(reg: u8, payload: u8) -> I2cTransaction {
+    I2cTransaction::write(PCF8523_I2C_ADDRESS, [reg, payload].to_vec())
+}
+
+// the mock panics instead of returning an error from a transaction, this fails every transaction and
+// passes everything else through
+struct FailingTransaction<'a>(&'a mut I2cMock);
+
+impl ErrorType for FailingTransaction<'_> {
+    type Error = ErrorKind;
+}
+
+impl I2c for FailingTransaction<'_> {
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.read(address, read)
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(address, write)
+    }
+
+    fn write_read(&mut self, address: u8, write: &[u8], read: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.write_read(address, write, read)
+    }
+
+    fn transaction(&mut self, _address: u8, _operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        Err(Other)
+    }
 }
\ No newline at end of file